@@ -0,0 +1,214 @@
+//! Actions per minute (APM) statistics.
+//!
+//! Every statistic is available in two forms. The overall form counts every command a player
+//! issued, leaving out commands the game sends on the player's behalf (such as camera tracking),
+//! which have an index of 0. The effective form also leaves out spam: commands that repeat the
+//! player's previous command exactly within a short window, such as clicking the same move order
+//! several times.
+//!
+//! Ticks start at 1, so minute `n` (counting from 0) covers ticks `n * 480 + 1` through
+//! `(n + 1) * 480`, and a command on tick 480 falls in the first minute.
+//!
+//! ```ignore
+//! let replay = vault::Replay::from_path("/path/to/replay.rec").unwrap();
+//! for report in vault::analytics::apm::apm(&replay, &Default::default()) {
+//!     println!("{}: {:.0} eAPM", report.player_id(), report.effective_apm());
+//! }
+//! ```
+
+use crate::command::Command;
+use crate::game_time::TICKS_PER_SECOND;
+use crate::player::Player;
+use crate::replay::Replay;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+const TICKS_PER_MINUTE: u32 = TICKS_PER_SECOND * 60;
+
+/// Settings for how APM statistics are calculated.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct ApmOptions {
+    spam_window: u32,
+    peak_window: u32,
+}
+
+impl ApmOptions {
+    /// Creates options with the given windows, both in ticks. A command is considered spam if it
+    /// repeats the player's previous command within `spam_window` ticks, and peak APM is measured
+    /// over the busiest `peak_window` ticks of the match. A `peak_window` of 0 is treated as 1.
+    /// `Command::Unknown` commands are never considered repeats, since what they were issued to
+    /// isn't known.
+    pub fn new(spam_window: u32, peak_window: u32) -> Self {
+        Self {
+            spam_window,
+            peak_window: peak_window.max(1),
+        }
+    }
+    /// The number of ticks within which a repeated command is considered spam.
+    pub fn spam_window(&self) -> u32 {
+        self.spam_window
+    }
+    /// The number of ticks peak APM is measured over.
+    pub fn peak_window(&self) -> u32 {
+        self.peak_window
+    }
+}
+
+impl Default for ApmOptions {
+    /// Repeats within 4 ticks (half a second) are spam, and peak APM is measured over a minute.
+    fn default() -> Self {
+        Self::new(4, TICKS_PER_MINUTE)
+    }
+}
+
+/// APM statistics for a single player. To create, see `apm` or `player_apm`.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::ApmReport"))]
+pub struct ApmReport {
+    player_id: u32,
+    apm: f64,
+    per_minute: Vec<u32>,
+    peak_apm: f64,
+    effective_apm: f64,
+    effective_per_minute: Vec<u32>,
+    effective_peak_apm: f64,
+}
+
+impl ApmReport {
+    /// The ID of the player the statistics belong to. See `Player::id`.
+    pub fn player_id(&self) -> u32 {
+        self.player_id
+    }
+    /// The number of commands the player issued per minute, averaged over the whole match. System
+    /// commands aren't counted.
+    pub fn apm(&self) -> f64 {
+        self.apm
+    }
+    /// The number of commands the player issued in each minute of the match, starting from the
+    /// first. The last entry covers whatever part of a minute the match lasted past the last full
+    /// minute. System commands aren't counted.
+    pub fn per_minute(&self) -> &[u32] {
+        &self.per_minute
+    }
+    /// The player's APM during the busiest stretch of the match. See `ApmOptions::peak_window`.
+    /// System commands aren't counted.
+    pub fn peak_apm(&self) -> f64 {
+        self.peak_apm
+    }
+    /// The same as `ApmReport::apm`, but also leaving out spam.
+    pub fn effective_apm(&self) -> f64 {
+        self.effective_apm
+    }
+    /// The same as `ApmReport::per_minute`, but also leaving out spam.
+    pub fn effective_per_minute(&self) -> &[u32] {
+        &self.effective_per_minute
+    }
+    /// The same as `ApmReport::peak_apm`, but also leaving out spam.
+    pub fn effective_peak_apm(&self) -> f64 {
+        self.effective_peak_apm
+    }
+}
+
+// this is safe as ApmReport does not contain any Ruby types
+#[cfg(feature = "magnus")]
+unsafe impl magnus::IntoValueFromNative for ApmReport {}
+
+/// Calculates APM statistics for every player in the replay, in the same order as
+/// `Replay::players`.
+pub fn apm(replay: &Replay, options: &ApmOptions) -> Vec<ApmReport> {
+    replay
+        .players()
+        .iter()
+        .map(|player| player_apm(player, replay.length(), options))
+        .collect()
+}
+
+/// Calculates APM statistics for a single player over a match lasting `length` ticks. See
+/// `Replay::length`.
+pub fn player_apm(player: &Player, length: usize, options: &ApmOptions) -> ApmReport {
+    let commands: Vec<Command> = player
+        .commands()
+        .into_iter()
+        .filter(|command| command.index() != 0)
+        .collect();
+    let length = length as u32;
+    let ticks: Vec<u32> = commands
+        .iter()
+        .map(|command| command.tick().ticks())
+        .collect();
+    let effective_ticks = effective_ticks(&commands, options.spam_window);
+
+    ApmReport {
+        player_id: player.id(),
+        apm: average(&ticks, length),
+        per_minute: per_minute(&ticks, length),
+        peak_apm: peak(&ticks, options.peak_window),
+        effective_apm: average(&effective_ticks, length),
+        effective_per_minute: per_minute(&effective_ticks, length),
+        effective_peak_apm: peak(&effective_ticks, options.peak_window),
+    }
+}
+
+// spam is measured against the previous player command whether or not that was spam itself, so
+// a long run of repeated clicks only counts once
+fn effective_ticks(commands: &[Command], spam_window: u32) -> Vec<u32> {
+    let mut ticks = Vec::new();
+    let mut previous: Option<&Command> = None;
+
+    for command in commands {
+        let spam = previous.into_iter().any(|previous| {
+            (command.tick() - previous.tick()).ticks() <= spam_window
+                && command.same_action(previous)
+        });
+        if !spam {
+            ticks.push(command.tick().ticks());
+        }
+        previous = Some(command);
+    }
+    ticks
+}
+
+fn average(ticks: &[u32], length: u32) -> f64 {
+    if length == 0 {
+        return 0.0;
+    }
+    ticks.len() as f64 * TICKS_PER_MINUTE as f64 / length as f64
+}
+
+// ticks start at 1, so the last tick of each minute is a multiple of TICKS_PER_MINUTE
+fn minute(tick: u32) -> u32 {
+    tick.saturating_sub(1) / TICKS_PER_MINUTE
+}
+
+fn per_minute(ticks: &[u32], length: u32) -> Vec<u32> {
+    let partial_minute = (length % TICKS_PER_MINUTE).min(1);
+    let minutes = ticks
+        .iter()
+        .map(|tick| minute(*tick) + 1)
+        .chain(std::iter::once(length / TICKS_PER_MINUTE + partial_minute))
+        .max()
+        .unwrap_or(0);
+    let mut counts = vec![0; minutes as usize];
+    for tick in ticks {
+        counts[minute(*tick) as usize] += 1;
+    }
+    counts
+}
+
+// ticks are sorted, so the busiest window can be found by sliding its start over each command
+fn peak(ticks: &[u32], window: u32) -> f64 {
+    let mut end = 0;
+    let mut busiest = 0;
+    for (start, tick) in ticks.iter().enumerate() {
+        while end < ticks.len() && ticks[end] < tick.saturating_add(window) {
+            end += 1;
+        }
+        busiest = busiest.max(end - start);
+    }
+    busiest as f64 * TICKS_PER_MINUTE as f64 / window as f64
+}
@@ -0,0 +1,4 @@
+//! Statistics derived from parsed replay data, for analysis that goes beyond what a replay records
+//! directly.
+
+pub mod apm;
@@ -0,0 +1,430 @@
+//! Lookup of the game attributes that pbgids refer to.
+//!
+//! Commands identify the units, upgrades, abilities, and structures they involve by pbgid, which
+//! is only meaningful alongside the game's attribute data. Replays don't include that data, so it
+//! has to be supplied separately, either by implementing `AttributeDb` over an existing source or
+//! by loading a dump of the attributes into an `AttributeArchive`. Attribute data is never fetched
+//! over the network.
+//!
+//! With the `attributes` feature enabled, dumps can be loaded from JSON or CSV. JSON dumps map
+//! each build version to the attributes of that build:
+//!
+//! ```ignore
+//! {
+//!   "10612": [
+//!     {
+//!       "pbgid": 198340,
+//!       "name": "...",
+//!       "category": "Squad",
+//!       "faction": "AfrikaKorps",
+//!       "cost": { "manpower": 280.0, "munitions": 0.0, "fuel": 0.0, "popcap": 5.0 },
+//!       "localized_name_id": "$11223344"
+//!     }
+//!   ]
+//! }
+//! ```
+//!
+//! The faction, cost, and localization ID of the attribute's name are optional. CSV dumps have one
+//! attribute per row, with the build version in the first column. An empty faction means the
+//! attribute isn't specific to a faction, and the `localized_name_id` column can be left out:
+//!
+//! ```ignore
+//! version,pbgid,name,category,faction,manpower,munitions,fuel,popcap,localized_name_id
+//! 10612,198340,...,Squad,AfrikaKorps,280,0,0,5,$11223344
+//! ```
+//!
+//! Since attributes such as costs change between patches, look up attributes using the build the
+//! replay was recorded on:
+//!
+//! ```ignore
+//! fn main() {
+//!     let archive = vault::attributes::AttributeArchive::from_json_path("/path/to/attributes.json")
+//!         .unwrap();
+//!     let replay = vault::Replay::from_path("/path/to/replay.rec").unwrap();
+//!     let db = archive.for_version(replay.version()).unwrap();
+//!     for command in replay.players()[0].build_commands() {
+//!         println!("{:?}", command.resolve(db).map(|attribute| attribute.name()));
+//!     }
+//! }
+//! ```
+
+use crate::locale::Locale;
+use crate::player::Faction;
+use std::collections::{BTreeMap, HashMap};
+
+#[cfg(feature = "attributes")]
+use std::{
+    error::Error,
+    fmt,
+    fmt::{Display, Formatter},
+    fs,
+    io::{self, Read},
+    path::Path,
+};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A source of game attributes for a single build of the game. Implement this to resolve pbgids
+/// against attribute data from somewhere other than an `AttributeArchive`.
+pub trait AttributeDb {
+    /// The attribute with the given pbgid, or `None` if there isn't one.
+    fn attribute(&self, pbgid: u32) -> Option<&Attribute>;
+}
+
+/// A single entry in the game's attribute data, such as a squad or an upgrade.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Attribute"))]
+pub struct Attribute {
+    pbgid: u32,
+    name: String,
+    category: AttributeCategory,
+    #[cfg_attr(feature = "serde", serde(default))]
+    faction: Option<Faction>,
+    #[cfg_attr(feature = "serde", serde(default))]
+    cost: Cost,
+    #[cfg_attr(feature = "serde", serde(default))]
+    localized_name_id: Option<String>,
+}
+
+impl Attribute {
+    /// Creates an attribute, for use when implementing `AttributeDb` over other attribute data.
+    pub fn new(
+        pbgid: u32,
+        name: String,
+        category: AttributeCategory,
+        faction: Option<Faction>,
+        cost: Cost,
+    ) -> Self {
+        Self {
+            pbgid,
+            name,
+            category,
+            faction,
+            cost,
+            localized_name_id: None,
+        }
+    }
+
+    /// Sets the localization ID of the attribute's in-game name, such as `$11223344`. See
+    /// `Attribute::localized_name`.
+    pub fn with_localized_name_id(self, localized_name_id: String) -> Self {
+        Self {
+            localized_name_id: Some(localized_name_id),
+            ..self
+        }
+    }
+
+    /// The pbgid that identifies this attribute in commands.
+    pub fn pbgid(&self) -> u32 {
+        self.pbgid
+    }
+    /// The name of the attribute's blueprint in the game's attribute data.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+    /// What kind of thing the attribute describes.
+    pub fn category(&self) -> AttributeCategory {
+        self.category
+    }
+    /// The faction the attribute belongs to, or `None` if it's available to every faction.
+    pub fn faction(&self) -> Option<Faction> {
+        self.faction
+    }
+    /// What the attribute costs to build or use.
+    pub fn cost(&self) -> Cost {
+        self.cost
+    }
+    /// The localization ID of the attribute's in-game name, such as `$11223344`, or `None` if the
+    /// attribute data didn't include one.
+    pub fn localized_name_id(&self) -> Option<&str> {
+        self.localized_name_id.as_deref()
+    }
+    /// The attribute's in-game name in the given locale, found by resolving
+    /// `Attribute::localized_name_id`. Returns `None` if there's no localization ID or the locale
+    /// doesn't have it.
+    pub fn localized_name<'a>(&self, locale: &'a Locale) -> Option<&'a str> {
+        self.localized_name_id
+            .as_deref()
+            .and_then(|id| locale.resolve(id))
+    }
+}
+
+/// The kinds of thing an `Attribute` can describe.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(
+    feature = "magnus",
+    magnus::wrap(class = "VaultCoh::AttributeCategory")
+)]
+pub enum AttributeCategory {
+    /// Units that can be produced or called in.
+    Squad,
+    /// Upgrades for squads or the whole player.
+    Upgrade,
+    /// Abilities used by squads, structures, or the player.
+    Ability,
+    /// Structures and other entities, such as those placed by `Command::ConstructEntity`.
+    Entity,
+    /// Battlegroups and their abilities.
+    Battlegroup,
+}
+
+/// The resources and population an `Attribute` costs.
+
+#[derive(Debug, Copy, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Cost"))]
+pub struct Cost {
+    manpower: f32,
+    munitions: f32,
+    fuel: f32,
+    popcap: f32,
+}
+
+impl Cost {
+    /// Creates a cost from amounts of each resource and population.
+    pub fn new(manpower: f32, munitions: f32, fuel: f32, popcap: f32) -> Self {
+        Self {
+            manpower,
+            munitions,
+            fuel,
+            popcap,
+        }
+    }
+
+    /// The amount of manpower.
+    pub fn manpower(&self) -> f32 {
+        self.manpower
+    }
+    /// The amount of munitions.
+    pub fn munitions(&self) -> f32 {
+        self.munitions
+    }
+    /// The amount of fuel.
+    pub fn fuel(&self) -> f32 {
+        self.fuel
+    }
+    /// The amount of population.
+    pub fn popcap(&self) -> f32 {
+        self.popcap
+    }
+}
+
+/// The attributes of a single build of the game, looked up by pbgid.
+
+#[derive(Debug, Clone, Default)]
+pub struct Attributes {
+    version: u16,
+    attributes: HashMap<u32, Attribute>,
+}
+
+impl Attributes {
+    /// Collects the given attributes for a build version. If several attributes share a pbgid,
+    /// the last one is kept.
+    pub fn new(version: u16, attributes: impl IntoIterator<Item = Attribute>) -> Self {
+        Self {
+            version,
+            attributes: attributes
+                .into_iter()
+                .map(|attribute| (attribute.pbgid, attribute))
+                .collect(),
+        }
+    }
+
+    /// The build version these attributes belong to. See `Replay::version`.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+    /// The number of attributes.
+    pub fn len(&self) -> usize {
+        self.attributes.len()
+    }
+    /// Whether there are no attributes.
+    pub fn is_empty(&self) -> bool {
+        self.attributes.is_empty()
+    }
+}
+
+impl AttributeDb for Attributes {
+    fn attribute(&self, pbgid: u32) -> Option<&Attribute> {
+        self.attributes.get(&pbgid)
+    }
+}
+
+/// The attributes of several builds of the game, such as a dump covering every patch that
+/// replays need to be analyzed for. Use `AttributeArchive::for_version` to get the attributes for
+/// the build a replay was recorded on.
+
+#[derive(Debug, Clone, Default)]
+pub struct AttributeArchive {
+    builds: BTreeMap<u16, Attributes>,
+}
+
+impl AttributeArchive {
+    /// Collects the given builds. If several builds share a version, the last one is kept.
+    pub fn new(builds: impl IntoIterator<Item = Attributes>) -> Self {
+        Self {
+            builds: builds
+                .into_iter()
+                .map(|attributes| (attributes.version, attributes))
+                .collect(),
+        }
+    }
+
+    /// Parses a JSON dump of attributes keyed by build version. See the module documentation for
+    /// the expected format. Requires the `attributes` feature.
+    #[cfg(feature = "attributes")]
+    pub fn from_json_slice(input: &[u8]) -> Result<AttributeArchive, AttributeError> {
+        let builds: BTreeMap<u16, Vec<Attribute>> = serde_json::from_slice(input)?;
+        Ok(Self::new(builds.into_iter().map(
+            |(version, attributes)| Attributes::new(version, attributes),
+        )))
+    }
+
+    /// Reads the JSON dump at the given path. Failure to read the file will return
+    /// `AttributeError::Io`; any other failures are the same as `AttributeArchive::from_json_slice`.
+    #[cfg(feature = "attributes")]
+    pub fn from_json_path(path: impl AsRef<Path>) -> Result<AttributeArchive, AttributeError> {
+        let data = fs::read(path)?;
+        Self::from_json_slice(&data)
+    }
+
+    /// Reads a CSV dump of attributes with a header row, one attribute per row. See the module
+    /// documentation for the expected columns. Requires the `attributes` feature.
+    #[cfg(feature = "attributes")]
+    pub fn from_csv_reader(reader: impl Read) -> Result<AttributeArchive, AttributeError> {
+        let mut builds: BTreeMap<u16, Vec<Attribute>> = BTreeMap::new();
+        for row in csv::Reader::from_reader(reader).deserialize() {
+            let row: CsvRow = row?;
+            builds.entry(row.version).or_default().push(Attribute {
+                pbgid: row.pbgid,
+                name: row.name,
+                category: row.category,
+                faction: row.faction,
+                cost: Cost::new(row.manpower, row.munitions, row.fuel, row.popcap),
+                localized_name_id: row.localized_name_id,
+            });
+        }
+
+        Ok(Self::new(builds.into_iter().map(
+            |(version, attributes)| Attributes::new(version, attributes),
+        )))
+    }
+
+    /// Reads the CSV dump at the given path. Failure to read the file will return
+    /// `AttributeError::Io`; any other failures are the same as `AttributeArchive::from_csv_reader`.
+    #[cfg(feature = "attributes")]
+    pub fn from_csv_path(path: impl AsRef<Path>) -> Result<AttributeArchive, AttributeError> {
+        Self::from_csv_reader(fs::File::open(path)?)
+    }
+
+    /// The build versions in the archive, from oldest to newest.
+    pub fn versions(&self) -> Vec<u16> {
+        self.builds.keys().copied().collect()
+    }
+    /// The attributes for the given build version. If the archive doesn't have that exact build,
+    /// the newest build before it is used instead, since most pbgids carry over between patches.
+    /// Returns `None` if every build in the archive is newer than the given version.
+    pub fn for_version(&self, version: u16) -> Option<&Attributes> {
+        self.builds
+            .range(..=version)
+            .next_back()
+            .map(|(_, attributes)| attributes)
+    }
+}
+
+#[cfg(feature = "attributes")]
+#[derive(Deserialize)]
+struct CsvRow {
+    version: u16,
+    pbgid: u32,
+    name: String,
+    category: AttributeCategory,
+    faction: Option<Faction>,
+    manpower: f32,
+    munitions: f32,
+    fuel: f32,
+    popcap: f32,
+    #[serde(default)]
+    localized_name_id: Option<String>,
+}
+
+/// Represents a failure to load a dump of attribute data. Requires the `attributes` feature.
+#[cfg(feature = "attributes")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AttributeError {
+    /// The dump could not be read from its source. `message` is the description of the underlying
+    /// I/O error.
+    Io {
+        kind: io::ErrorKind,
+        message: String,
+    },
+    /// The dump isn't valid JSON or CSV, or doesn't match the expected format. `line` is the line
+    /// the failure was detected on, if known, and `reason` is the parser's description of it.
+    Malformed { line: Option<u64>, reason: String },
+}
+
+#[cfg(feature = "attributes")]
+impl Display for AttributeError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AttributeError::Io { message, .. } => {
+                write!(f, "failed to read attributes: {}", message)
+            }
+            AttributeError::Malformed { reason, .. } => {
+                write!(f, "malformed attributes: {}", reason)
+            }
+        }
+    }
+}
+
+#[cfg(feature = "attributes")]
+impl Error for AttributeError {}
+
+#[cfg(feature = "attributes")]
+impl From<io::Error> for AttributeError {
+    fn from(err: io::Error) -> Self {
+        AttributeError::Io {
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "attributes")]
+impl From<serde_json::Error> for AttributeError {
+    fn from(err: serde_json::Error) -> Self {
+        if let Some(kind) = err.io_error_kind() {
+            return AttributeError::Io {
+                kind,
+                message: err.to_string(),
+            };
+        }
+
+        AttributeError::Malformed {
+            line: Some(err.line() as u64),
+            reason: err.to_string(),
+        }
+    }
+}
+
+#[cfg(feature = "attributes")]
+impl From<csv::Error> for AttributeError {
+    fn from(err: csv::Error) -> Self {
+        if let csv::ErrorKind::Io(io) = err.kind() {
+            return AttributeError::Io {
+                kind: io.kind(),
+                message: io.to_string(),
+            };
+        }
+
+        AttributeError::Malformed {
+            line: err.position().map(|position| position.line()),
+            reason: err.to_string(),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Representation of strategic point capture orders.
+
+use crate::command::Command;
+use crate::command_data::Target;
+use crate::game_time::GameTime;
+use crate::player::Player;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A strategic point (territory point, fuel or munitions point, victory point, etc.) that at least
+/// one player ordered squads to capture. Points are identified by the internal identifier of their
+/// entity, which stays the same for the whole match, so orders from different players targeting
+/// the same point can be compared. To access, see `Replay::capture_timeline`.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::StrategicPoint"))]
+pub struct StrategicPoint {
+    entity_id: u32,
+    captures: Vec<CaptureOrder>,
+}
+
+impl StrategicPoint {
+    /// The internal identifier of the point's entity, as given by `Target::Entity` in the capture
+    /// commands targeting it.
+    pub fn entity_id(&self) -> u32 {
+        self.entity_id
+    }
+    /// Every order to capture the point, sorted chronologically.
+    pub fn captures(&self) -> Vec<CaptureOrder> {
+        self.captures.clone()
+    }
+    /// The tick at which the player with the given ID first ordered squads to capture the point,
+    /// or `None` if they never did.
+    pub fn first_capture_by(&self, player_id: u32) -> Option<GameTime> {
+        self.captures
+            .iter()
+            .find(|capture| capture.player_id == player_id)
+            .map(|capture| capture.tick)
+    }
+}
+
+// this is safe as StrategicPoint does not contain any Ruby types
+#[cfg(feature = "magnus")]
+unsafe impl magnus::IntoValueFromNative for StrategicPoint {}
+
+/// A single order to capture a strategic point.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::CaptureOrder"))]
+pub struct CaptureOrder {
+    tick: GameTime,
+    player_id: u32,
+    squad_ids: Vec<u32>,
+}
+
+impl CaptureOrder {
+    /// The tick at which the order was issued.
+    pub fn tick(&self) -> GameTime {
+        self.tick
+    }
+    /// The ID of the player who issued the order. See `Player::id`.
+    pub fn player_id(&self) -> u32 {
+        self.player_id
+    }
+    /// The internal identifiers of the squads ordered to capture the point.
+    pub fn squad_ids(&self) -> &[u32] {
+        &self.squad_ids
+    }
+}
+
+// this is safe as CaptureOrder does not contain any Ruby types
+#[cfg(feature = "magnus")]
+unsafe impl magnus::IntoValueFromNative for CaptureOrder {}
+
+pub(crate) fn capture_timeline_from_players(players: &[Player]) -> Vec<StrategicPoint> {
+    let mut orders: Vec<(u32, CaptureOrder)> = players
+        .iter()
+        .flat_map(|player| {
+            player
+                .commands()
+                .into_iter()
+                .filter_map(move |command| match command {
+                    Command::Capture(data) => match data.target() {
+                        Some(Target::Entity(entity_id)) => Some((
+                            entity_id,
+                            CaptureOrder {
+                                tick: data.tick(),
+                                player_id: player.id(),
+                                squad_ids: data.squad_ids().to_vec(),
+                            },
+                        )),
+                        _ => None,
+                    },
+                    _ => None,
+                })
+        })
+        .collect();
+    // stable, so orders issued on the same tick keep the order of the player list
+    orders.sort_by_key(|(_, order)| order.tick);
+
+    let mut points: Vec<StrategicPoint> = Vec::new();
+    for (entity_id, order) in orders {
+        match points.iter_mut().find(|point| point.entity_id == entity_id) {
+            Some(point) => point.captures.push(order),
+            None => points.push(StrategicPoint {
+                entity_id,
+                captures: vec![order],
+            }),
+        }
+    }
+    points
+}
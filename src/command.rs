@@ -1,11 +1,19 @@
 //! Wrapper for Company of Heroes 3 player commands.
 
 use crate::{
-    command_data::{Empty, Pbgid, Sourced, SourcedIndex, SourcedPbgid, Unknown},
+    attributes::{Attribute, AttributeDb},
+    command_data::{
+        Cheat, CheatKind, Empty, Pbgid, Placement, Position, SelectedIndex, Sourced, SourcedIndex,
+        SourcedPbgid, Target, Targeted, TargetedPbgid, Unknown,
+    },
     command_type::CommandType,
     data::ticks,
+    errors::VaultError,
+    game_time::GameTime,
 };
 
+use std::mem;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -15,32 +23,255 @@ use serde::{Deserialize, Serialize};
 /// Commands are collected during tick parsing and then associated with the `Player` instance that
 /// sent them. To access, see `Player::commands`.
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Command"))]
 pub enum Command {
     AITakeover(Empty),
+    AttackMove(Targeted),
     BuildGlobalUpgrade(SourcedPbgid),
     BuildSquad(SourcedPbgid),
+    BuildSquadUpgrade(TargetedPbgid),
+    BuildStructure(Targeted),
     CancelConstruction(Sourced),
     CancelProduction(SourcedIndex),
-    ConstructEntity(Pbgid),
+    CancelSquadProduction(SelectedIndex),
+    Capture(Targeted),
+    CaptureTeamWeapon(Targeted),
+    Cheat(Cheat),
+    ConstructEntity(Placement),
+    DeselectBattlegroupAbilities(Empty),
+    Face(Targeted),
+    Load(Targeted),
+    Move(Targeted),
+    Recrew(Targeted),
+    Reinforce(TargetedPbgid),
+    Retreat(Targeted),
     SelectBattlegroup(Pbgid),
     SelectBattlegroupAbility(Pbgid),
+    Stop(Targeted),
+    Surrender(Empty),
+    Unload(Targeted),
+    UnloadSquads(Targeted),
     UseAbility(SourcedPbgid),
     UseBattlegroupAbility(Pbgid),
+    UseSquadAbility(TargetedPbgid),
     Unknown(Unknown),
 }
 
 impl Command {
-    pub(crate) fn from_data_command_at_tick(command: ticks::Command, tick: u32) -> Self {
-        match command.data {
+    /// The tick at which the command was executed, regardless of which variant it is. See the
+    /// `tick` method of the specific command formats for details.
+    pub fn tick(&self) -> GameTime {
+        match self {
+            Self::AITakeover(data)
+            | Self::DeselectBattlegroupAbilities(data)
+            | Self::Surrender(data) => data.tick(),
+            Self::AttackMove(data)
+            | Self::BuildStructure(data)
+            | Self::Capture(data)
+            | Self::CaptureTeamWeapon(data)
+            | Self::Face(data)
+            | Self::Load(data)
+            | Self::Move(data)
+            | Self::Recrew(data)
+            | Self::Retreat(data)
+            | Self::Stop(data)
+            | Self::Unload(data)
+            | Self::UnloadSquads(data) => data.tick(),
+            Self::BuildGlobalUpgrade(data) | Self::BuildSquad(data) | Self::UseAbility(data) => {
+                data.tick()
+            }
+            Self::BuildSquadUpgrade(data) | Self::Reinforce(data) | Self::UseSquadAbility(data) => {
+                data.tick()
+            }
+            Self::CancelConstruction(data) => data.tick(),
+            Self::CancelProduction(data) => data.tick(),
+            Self::CancelSquadProduction(data) => data.tick(),
+            Self::Cheat(data) => data.tick(),
+            Self::ConstructEntity(data) => data.tick(),
+            Self::SelectBattlegroup(data)
+            | Self::SelectBattlegroupAbility(data)
+            | Self::UseBattlegroupAbility(data) => data.tick(),
+            Self::Unknown(data) => data.tick(),
+        }
+    }
+
+    /// The index of the command relative to the player who issued it, regardless of which variant
+    /// it is. System commands that were not triggered by player action have an index of 0. See the
+    /// `index` method of the specific command formats for details.
+    pub fn index(&self) -> u32 {
+        match self {
+            Self::AITakeover(data)
+            | Self::DeselectBattlegroupAbilities(data)
+            | Self::Surrender(data) => data.index(),
+            Self::AttackMove(data)
+            | Self::BuildStructure(data)
+            | Self::Capture(data)
+            | Self::CaptureTeamWeapon(data)
+            | Self::Face(data)
+            | Self::Load(data)
+            | Self::Move(data)
+            | Self::Recrew(data)
+            | Self::Retreat(data)
+            | Self::Stop(data)
+            | Self::Unload(data)
+            | Self::UnloadSquads(data) => data.index(),
+            Self::BuildGlobalUpgrade(data) | Self::BuildSquad(data) | Self::UseAbility(data) => {
+                data.index()
+            }
+            Self::BuildSquadUpgrade(data) | Self::Reinforce(data) | Self::UseSquadAbility(data) => {
+                data.index()
+            }
+            Self::CancelConstruction(data) => data.index(),
+            Self::CancelProduction(data) => data.index(),
+            Self::CancelSquadProduction(data) => data.index(),
+            Self::Cheat(data) => data.index(),
+            Self::ConstructEntity(data) => data.index(),
+            Self::SelectBattlegroup(data)
+            | Self::SelectBattlegroupAbility(data)
+            | Self::UseBattlegroupAbility(data) => data.index(),
+            Self::Unknown(data) => data.index(),
+        }
+    }
+
+    /// The pbgid of the unit, upgrade, ability, structure, or battlegroup the command involves, or
+    /// `None` if the command doesn't involve one. See `Command::resolve` to look it up.
+    pub fn pbgid(&self) -> Option<u32> {
+        match self {
+            Self::BuildGlobalUpgrade(data) | Self::BuildSquad(data) | Self::UseAbility(data) => {
+                Some(data.pbgid())
+            }
+            Self::BuildSquadUpgrade(data) | Self::Reinforce(data) | Self::UseSquadAbility(data) => {
+                data.pbgid()
+            }
+            Self::SelectBattlegroup(data)
+            | Self::SelectBattlegroupAbility(data)
+            | Self::UseBattlegroupAbility(data) => Some(data.pbgid()),
+            Self::ConstructEntity(data) => Some(data.pbgid()),
+            _ => None,
+        }
+    }
+    /// Looks up the attribute the command's pbgid refers to in the given attribute database. This
+    /// is `None` if the command doesn't involve a pbgid (see `Command::pbgid`), or if the database
+    /// doesn't contain it. The database should be for the build the replay was recorded on; see
+    /// `attributes::AttributeArchive::for_version`.
+    pub fn resolve<'a, D: AttributeDb + ?Sized>(&self, db: &'a D) -> Option<&'a Attribute> {
+        self.pbgid().and_then(|pbgid| db.attribute(pbgid))
+    }
+    /// The broad kind of action the command represents, useful for filtering a player's commands
+    /// or `Replay::events`. See `CommandCategory` for which commands fall under each category.
+    pub fn category(&self) -> CommandCategory {
+        match self {
+            Self::BuildGlobalUpgrade(_)
+            | Self::BuildSquad(_)
+            | Self::BuildSquadUpgrade(_)
+            | Self::CancelProduction(_)
+            | Self::CancelSquadProduction(_)
+            | Self::Reinforce(_) => CommandCategory::Production,
+            Self::BuildStructure(_) | Self::CancelConstruction(_) | Self::ConstructEntity(_) => {
+                CommandCategory::Construction
+            }
+            Self::AttackMove(_)
+            | Self::Face(_)
+            | Self::Load(_)
+            | Self::Move(_)
+            | Self::Retreat(_)
+            | Self::Stop(_)
+            | Self::Unload(_)
+            | Self::UnloadSquads(_) => CommandCategory::Movement,
+            Self::Capture(_) | Self::CaptureTeamWeapon(_) | Self::Recrew(_) => {
+                CommandCategory::Combat
+            }
+            Self::UseAbility(_) | Self::UseSquadAbility(_) => CommandCategory::Ability,
+            Self::DeselectBattlegroupAbilities(_)
+            | Self::SelectBattlegroup(_)
+            | Self::SelectBattlegroupAbility(_)
+            | Self::UseBattlegroupAbility(_) => CommandCategory::Battlegroup,
+            Self::AITakeover(_) | Self::Cheat(_) | Self::Surrender(_) => CommandCategory::Player,
+            Self::Unknown(_) => CommandCategory::Unknown,
+        }
+    }
+
+    // whether both commands are the same order issued to the same things, ignoring when they were
+    // issued. unknown commands are never the same, since only their action type is known
+    pub(crate) fn same_action(&self, other: &Command) -> bool {
+        match (self.action(), other.action()) {
+            (Action::Unknown, _) | (_, Action::Unknown) => false,
+            (this, other_action) => {
+                mem::discriminant(self) == mem::discriminant(other) && this == other_action
+            }
+        }
+    }
+
+    fn action(&self) -> Action<'_> {
+        match self {
+            Self::AITakeover(_) | Self::DeselectBattlegroupAbilities(_) | Self::Surrender(_) => {
+                Action::Empty
+            }
+            Self::AttackMove(data)
+            | Self::BuildStructure(data)
+            | Self::Capture(data)
+            | Self::CaptureTeamWeapon(data)
+            | Self::Face(data)
+            | Self::Load(data)
+            | Self::Move(data)
+            | Self::Recrew(data)
+            | Self::Retreat(data)
+            | Self::Stop(data)
+            | Self::Unload(data)
+            | Self::UnloadSquads(data) => Action::Targeted(
+                data.squad_ids(),
+                data.entity_ids(),
+                data.target(),
+                data.queued(),
+            ),
+            Self::BuildGlobalUpgrade(data) | Self::BuildSquad(data) | Self::UseAbility(data) => {
+                Action::Sourced(data.source_identifier(), Some(data.pbgid()))
+            }
+            Self::BuildSquadUpgrade(data) | Self::Reinforce(data) | Self::UseSquadAbility(data) => {
+                Action::TargetedPbgid(
+                    data.squad_ids(),
+                    data.entity_ids(),
+                    data.pbgid(),
+                    data.target(),
+                )
+            }
+            Self::CancelConstruction(data) => Action::Sourced(data.source_identifier(), None),
+            Self::CancelProduction(data) => {
+                Action::Sourced(data.source_identifier(), Some(data.queue_index()))
+            }
+            Self::CancelSquadProduction(data) => {
+                Action::Selected(data.squad_ids(), data.entity_ids(), data.queue_index())
+            }
+            Self::Cheat(data) => Action::Cheat(data.kind(), data.parameters()),
+            Self::ConstructEntity(data) => Action::Placement(
+                data.pbgid(),
+                data.position(),
+                data.end_position(),
+                data.facing(),
+                data.squad_ids(),
+            ),
+            Self::SelectBattlegroup(data)
+            | Self::SelectBattlegroupAbility(data)
+            | Self::UseBattlegroupAbility(data) => Action::Pbgid(data.pbgid()),
+            Self::Unknown(_) => Action::Unknown,
+        }
+    }
+
+    pub(crate) fn from_data_command_at_tick(
+        command: ticks::Command,
+        tick: u32,
+    ) -> Result<Self, VaultError> {
+        let tick = GameTime::from_ticks(tick);
+        Ok(match command.data {
             ticks::CommandData::Empty => match command.action_type {
-                CommandType::PCMD_AIPlayer => Self::AITakeover(Empty::new(tick)),
-                _ => panic!(
-                    "an empty command isn't being handled here! command type {:?}",
-                    command.action_type
-                ),
+                CommandType::PCMD_AIPlayer => Self::AITakeover(Empty::new(tick, command.index)),
+                CommandType::PCMD_Surrender => Self::Surrender(Empty::new(tick, command.index)),
+                CommandType::PCMD_TentativeUpgradeRemoveAll => {
+                    Self::DeselectBattlegroupAbilities(Empty::new(tick, command.index))
+                }
+                _ => return Err(Self::mismatch(&command)),
             },
             ticks::CommandData::Pbgid(pbgid) => match command.action_type {
                 CommandType::PCMD_Ability => {
@@ -49,16 +280,10 @@ impl Command {
                 CommandType::PCMD_InstantUpgrade => {
                     Self::SelectBattlegroup(Pbgid::new(tick, command.index, pbgid))
                 }
-                CommandType::PCMD_PlaceAndConstructEntities => {
-                    Self::ConstructEntity(Pbgid::new(tick, command.index, pbgid))
-                }
                 CommandType::PCMD_TentativeUpgrade => {
                     Self::SelectBattlegroupAbility(Pbgid::new(tick, command.index, pbgid))
                 }
-                _ => panic!(
-                    "a pbgid command isn't being handled here! command type {:?}",
-                    command.action_type
-                ),
+                _ => return Err(Self::mismatch(&command)),
             },
             ticks::CommandData::SourcedPbgid(pbgid, source_identifier) => match command.action_type
             {
@@ -80,19 +305,13 @@ impl Command {
                     pbgid,
                     source_identifier,
                 )),
-                _ => panic!(
-                    "a sourced pbgid command isn't being handled here! command type {:?}",
-                    command.action_type
-                ),
+                _ => return Err(Self::mismatch(&command)),
             },
             ticks::CommandData::Sourced(source_identifier) => match command.action_type {
                 CommandType::CMD_CancelConstruction => {
                     Self::CancelConstruction(Sourced::new(tick, command.index, source_identifier))
                 }
-                _ => panic!(
-                    "a sourced command isn't being handled here! command type {:?}",
-                    command.action_type
-                ),
+                _ => return Err(Self::mismatch(&command)),
             },
             ticks::CommandData::SourcedIndex(source_identifier, queue_index) => {
                 match command.action_type {
@@ -102,15 +321,90 @@ impl Command {
                         source_identifier,
                         queue_index,
                     )),
-                    _ => panic!(
-                        "a sourced command isn't being handled here! command type {:?}",
-                        command.action_type
+                    _ => return Err(Self::mismatch(&command)),
+                }
+            }
+            ticks::CommandData::Targeted(ref selection, target, queued) => {
+                let data = Targeted::new(tick, command.index, selection.clone(), target, queued);
+                match command.action_type {
+                    CommandType::CMD_Move
+                    | CommandType::SCMD_Move
+                    | CommandType::FCMD_FormationSquadGroupMove => Self::Move(data),
+                    CommandType::SCMD_AttackMove => Self::AttackMove(data),
+                    CommandType::SCMD_Retreat => Self::Retreat(data),
+                    CommandType::SCMD_Stop => Self::Stop(data),
+                    CommandType::SCMD_Face => Self::Face(data),
+                    CommandType::SCMD_CaptureTeamWeapon => Self::CaptureTeamWeapon(data),
+                    CommandType::SCMD_Recrew => Self::Recrew(data),
+                    CommandType::SCMD_Load => Self::Load(data),
+                    CommandType::SCMD_Unload => Self::Unload(data),
+                    CommandType::CMD_UnloadSquads | CommandType::SCMD_UnloadSquads => {
+                        Self::UnloadSquads(data)
+                    }
+                    CommandType::CMD_Capture | CommandType::SCMD_Capture => Self::Capture(data),
+                    CommandType::CMD_BuildStructure | CommandType::SCMD_BuildStructure => {
+                        Self::BuildStructure(data)
+                    }
+                    _ => return Err(Self::mismatch(&command)),
+                }
+            }
+            ticks::CommandData::TargetedPbgid(ref selection, pbgid, target) => {
+                let data =
+                    TargetedPbgid::new(tick, command.index, selection.clone(), pbgid, target);
+                match command.action_type {
+                    CommandType::SCMD_Ability => Self::UseSquadAbility(data),
+                    CommandType::SCMD_Upgrade => Self::BuildSquadUpgrade(data),
+                    CommandType::SCMD_ReinforceUnit => Self::Reinforce(data),
+                    _ => return Err(Self::mismatch(&command)),
+                }
+            }
+            ticks::CommandData::SelectedIndex(ref selection, queue_index) => {
+                match command.action_type {
+                    CommandType::SCMD_CancelProduction => Self::CancelSquadProduction(
+                        SelectedIndex::new(tick, command.index, selection.clone(), queue_index),
                     ),
+                    _ => return Err(Self::mismatch(&command)),
+                }
+            }
+            ticks::CommandData::Placement(pbgid, start, end, facing, ref squad_ids) => {
+                match command.action_type {
+                    CommandType::PCMD_PlaceAndConstructEntities => {
+                        Self::ConstructEntity(Placement::new(
+                            tick,
+                            command.index,
+                            pbgid,
+                            start,
+                            end,
+                            facing,
+                            squad_ids.clone(),
+                        ))
+                    }
+                    _ => return Err(Self::mismatch(&command)),
+                }
+            }
+            ticks::CommandData::Parameters(ref parameters) => {
+                let cheat = |kind| Cheat::new(tick, command.index, kind, parameters.clone());
+                match command.action_type {
+                    CommandType::PCMD_CheatResources => Self::Cheat(cheat(CheatKind::Resources)),
+                    CommandType::PCMD_CheatRevealAll => Self::Cheat(cheat(CheatKind::RevealAll)),
+                    CommandType::PCMD_CheatBuildTime => Self::Cheat(cheat(CheatKind::BuildTime)),
+                    CommandType::PCMD_CheatIgnoreCosts => {
+                        Self::Cheat(cheat(CheatKind::IgnoreCosts))
+                    }
+                    _ => return Err(Self::mismatch(&command)),
                 }
             }
             ticks::CommandData::Unknown => {
                 Self::Unknown(Unknown::new(tick, command.index, command.action_type))
             }
+        })
+    }
+
+    fn mismatch(command: &ticks::Command) -> VaultError {
+        VaultError::CommandMismatch {
+            offset: command.offset,
+            path: "TICKS".to_string(),
+            command_type: command.action_type,
         }
     }
 }
@@ -119,6 +413,46 @@ impl Command {
 #[cfg(feature = "magnus")]
 unsafe impl magnus::IntoValueFromNative for Command {}
 
+// the parts of a command's data that identify the order it gives, borrowed from the command so
+// that commands can be compared without their tick and index
+#[derive(PartialEq)]
+enum Action<'a> {
+    Empty,
+    Pbgid(u32),
+    Sourced(u16, Option<u32>),
+    Selected(&'a [u32], &'a [u32], u32),
+    Targeted(&'a [u32], &'a [u32], Option<Target>, bool),
+    TargetedPbgid(&'a [u32], &'a [u32], Option<u32>, Option<Target>),
+    Placement(u32, Position, Position, Position, &'a [u32]),
+    Cheat(CheatKind, &'a [u8]),
+    Unknown,
+}
+
+/// The broad kinds of action a `Command` can represent. To access, see `Command::category`.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::CommandCategory"))]
+pub enum CommandCategory {
+    /// Queueing, reinforcing, or cancelling units and upgrades.
+    Production,
+    /// Placing, building, or cancelling structures.
+    Construction,
+    /// Orders that move units or change where they are going, including transport orders.
+    Movement,
+    /// Orders that change how units fight, such as capturing points and crewing team weapons.
+    Combat,
+    /// Using abilities from structures or squads.
+    Ability,
+    /// Selecting a battlegroup, or selecting and using its abilities.
+    Battlegroup,
+    /// Actions that affect the player as a whole, such as surrendering, being taken over by AI, and
+    /// cheats.
+    Player,
+    /// Commands that haven't been decoded yet. See `Command::Unknown`.
+    Unknown,
+}
+
 #[derive(Clone, Debug)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg(feature = "raw")]
@@ -0,0 +1,79 @@
+use crate::command_type::CommandType;
+use crate::game_time::GameTime;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format for developer cheats. Cheats can't be used in normal multiplayer matches, so
+/// their presence in a replay usually means the match was played with a modified game.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Cheat {
+    tick: GameTime,
+    index: u32,
+    kind: CheatKind,
+    parameters: Vec<u8>,
+}
+
+impl Cheat {
+    pub(crate) fn new(tick: GameTime, index: u32, kind: CheatKind, parameters: Vec<u8>) -> Self {
+        Self {
+            tick,
+            index,
+            kind,
+            parameters,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. See `GameTime` for converting
+    /// it to seconds or formatting it like the in-game clock.
+    pub fn tick(&self) -> GameTime {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// Which cheat was used.
+    pub fn kind(&self) -> CheatKind {
+        self.kind
+    }
+    /// The raw parameters the cheat was issued with. The layout of these parameters hasn't been
+    /// mapped out, so they are exposed as-is; in particular the amount granted by a `Resources`
+    /// cheat isn't decoded, as no replay with one has been available to check it against.
+    pub fn parameters(&self) -> &[u8] {
+        &self.parameters
+    }
+}
+
+/// The developer cheats that can be recorded in a replay.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum CheatKind {
+    /// Grants resources to the player.
+    Resources,
+    /// Removes the fog of war.
+    RevealAll,
+    /// Makes production and construction instant.
+    BuildTime,
+    /// Makes production and construction free.
+    IgnoreCosts,
+}
+
+impl CheatKind {
+    pub(crate) fn from_command_type(command_type: CommandType) -> Option<Self> {
+        match command_type {
+            CommandType::PCMD_CheatResources => Some(Self::Resources),
+            CommandType::PCMD_CheatRevealAll => Some(Self::RevealAll),
+            CommandType::PCMD_CheatBuildTime => Some(Self::BuildTime),
+            CommandType::PCMD_CheatIgnoreCosts => Some(Self::IgnoreCosts),
+            _ => None,
+        }
+    }
+}
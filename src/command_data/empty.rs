@@ -1,24 +1,34 @@
+use crate::game_time::GameTime;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
-/// An empty command format with no additional context.
+/// An empty command format with no additional context beyond when it was issued.
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Empty {
-    tick: u32,
+    tick: GameTime,
+    index: u32,
 }
 
 impl Empty {
-    pub(crate) fn new(tick: u32) -> Self {
-        Self { tick }
+    pub(crate) fn new(tick: GameTime, index: u32) -> Self {
+        Self { tick, index }
     }
 
     /// This value is the tick at which the command was found while parsing the replay, which
-    /// represents the time in the replay at which it was executed. Because CoH3's engine runs at 8
-    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
-    /// replay began, which will tell you when this command was executed.
-    pub fn tick(&self) -> u32 {
+    /// represents the time in the replay at which it was executed. See `GameTime` for converting
+    /// it to seconds or formatting it like the in-game clock.
+    pub fn tick(&self) -> GameTime {
         self.tick
     }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game. System commands that were
+    /// not triggered by player action will have an index of 0.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
 }
@@ -1,15 +1,30 @@
 //! Representations of replay command data formats.
 
+mod cheat;
 mod empty;
 mod pbgid;
+mod placement;
+mod position;
+mod selected_index;
 mod sourced;
 mod sourced_index;
 mod sourced_pbgid;
+mod target;
+mod targeted;
+mod targeted_pbgid;
 mod unknown;
 
+pub use crate::command_data::cheat::Cheat;
+pub use crate::command_data::cheat::CheatKind;
 pub use crate::command_data::empty::Empty;
 pub use crate::command_data::pbgid::Pbgid;
+pub use crate::command_data::placement::Placement;
+pub use crate::command_data::position::Position;
+pub use crate::command_data::selected_index::SelectedIndex;
 pub use crate::command_data::sourced::Sourced;
 pub use crate::command_data::sourced_index::SourcedIndex;
 pub use crate::command_data::sourced_pbgid::SourcedPbgid;
+pub use crate::command_data::target::Target;
+pub use crate::command_data::targeted::Targeted;
+pub use crate::command_data::targeted_pbgid::TargetedPbgid;
 pub use crate::command_data::unknown::Unknown;
@@ -0,0 +1,100 @@
+use crate::command_data::Position;
+use crate::data::ticks::Vector;
+use crate::game_time::GameTime;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format for placing a structure on the map, such as a building, a defensive emplacement
+/// or a line of sandbags.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Placement {
+    tick: GameTime,
+    index: u32,
+    pbgid: u32,
+    position: Position,
+    end_position: Position,
+    facing: Position,
+    squad_ids: Vec<u32>,
+}
+
+impl Placement {
+    pub(crate) fn new(
+        tick: GameTime,
+        index: u32,
+        pbgid: u32,
+        start: Vector,
+        end: Vector,
+        facing: Vector,
+        squad_ids: Vec<u32>,
+    ) -> Self {
+        let (x, y, z) = start;
+        let (end_x, end_y, end_z) = end;
+        let (facing_x, facing_y, facing_z) = facing;
+
+        Self {
+            tick,
+            index,
+            pbgid,
+            position: Position::new(x, y, z),
+            end_position: Position::new(end_x, end_y, end_z),
+            facing: Position::new(facing_x, facing_y, facing_z),
+            squad_ids,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. See `GameTime` for converting
+    /// it to seconds or formatting it like the in-game clock.
+    pub fn tick(&self) -> GameTime {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// This value is the pbgid of the structure being placed. A pbgid is a unique identifier for
+    /// a specific entity blueprint in the game's attribute data.
+    pub fn pbgid(&self) -> u32 {
+        self.pbgid
+    }
+    /// The world space position the structure was placed at. For structures placed as a line,
+    /// this is where the line starts.
+    pub fn position(&self) -> Position {
+        self.position
+    }
+    /// The world space position at which the placed line of structures ends. This is the same as
+    /// `Placement::position` unless the structure can be placed as a line, such as sandbags or
+    /// barbed wire.
+    pub fn end_position(&self) -> Position {
+        self.end_position
+    }
+    /// Whether the structure was placed as a line running between `Placement::position` and
+    /// `Placement::end_position`.
+    pub fn is_line(&self) -> bool {
+        self.position != self.end_position
+    }
+    /// A world space position one unit in front of `Placement::position`, in the direction the
+    /// structure is facing.
+    pub fn facing(&self) -> Position {
+        self.facing
+    }
+    /// The rotation of the structure around the vertical axis in degrees, derived from
+    /// `Placement::facing`. A rotation of 0 faces towards positive `z`, and rotations increase
+    /// clockwise when looking down at the map.
+    pub fn rotation(&self) -> f32 {
+        let dx = self.facing.x() - self.position.x();
+        let dz = self.facing.z() - self.position.z();
+        dx.atan2(dz).to_degrees()
+    }
+    /// The internal identifiers of the squads ordered to construct the structure. See
+    /// `Targeted::squad_ids`.
+    pub fn squad_ids(&self) -> &[u32] {
+        &self.squad_ids
+    }
+}
@@ -0,0 +1,33 @@
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A point in world space on the map a command was issued on. The game uses a Y-up coordinate
+/// system, so `x` and `z` give the position on the map's surface while `y` is the height of the
+/// terrain at that point.
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Position {
+    x: f32,
+    y: f32,
+    z: f32,
+}
+
+impl Position {
+    pub(crate) fn new(x: f32, y: f32, z: f32) -> Self {
+        Self { x, y, z }
+    }
+
+    /// The position along the map's horizontal axis.
+    pub fn x(&self) -> f32 {
+        self.x
+    }
+    /// The height of the position.
+    pub fn y(&self) -> f32 {
+        self.y
+    }
+    /// The position along the map's vertical axis.
+    pub fn z(&self) -> f32 {
+        self.z
+    }
+}
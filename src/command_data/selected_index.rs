@@ -0,0 +1,64 @@
+use crate::command_data::targeted::split_selection;
+use crate::game_time::GameTime;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format for orders issued to a selection of squads or entities that refer to a
+/// position in their build queue, such as cancelling an upgrade a squad is working on. This is the
+/// selection counterpart to `SourcedIndex`.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct SelectedIndex {
+    tick: GameTime,
+    index: u32,
+    squad_ids: Vec<u32>,
+    entity_ids: Vec<u32>,
+    queue_index: u32,
+}
+
+impl SelectedIndex {
+    pub(crate) fn new(tick: GameTime, index: u32, selection: Vec<u32>, queue_index: u32) -> Self {
+        let (squad_ids, entity_ids) = split_selection(selection);
+
+        Self {
+            tick,
+            index,
+            squad_ids,
+            entity_ids,
+            queue_index,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. See `GameTime` for converting
+    /// it to seconds or formatting it like the in-game clock.
+    pub fn tick(&self) -> GameTime {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// The internal identifiers of the squads the command was issued to. See
+    /// `Targeted::squad_ids`.
+    pub fn squad_ids(&self) -> &[u32] {
+        &self.squad_ids
+    }
+    /// The internal identifiers of the entities (vehicles, buildings, etc. that aren't part of a
+    /// squad) the command was issued to. See `Targeted::entity_ids`.
+    pub fn entity_ids(&self) -> &[u32] {
+        &self.entity_ids
+    }
+    /// The index of the position in the selection's build queue that this command corresponds to.
+    /// Unlike structure queues (see `SourcedIndex::queue_index`), these don't line up with the
+    /// number of upgrades the player queued on the squad, since the squad's queue also counts items
+    /// the replay doesn't record.
+    pub fn queue_index(&self) -> u32 {
+        self.queue_index
+    }
+}
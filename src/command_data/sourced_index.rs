@@ -1,19 +1,26 @@
+use crate::game_time::GameTime;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// A command format with both a source identifier and a queue index.
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct SourcedIndex {
-    tick: u32,
+    tick: GameTime,
     index: u32,
     source_identifier: u16,
     queue_index: u32,
 }
 
 impl SourcedIndex {
-    pub(crate) fn new(tick: u32, index: u32, source_identifier: u16, queue_index: u32) -> Self {
+    pub(crate) fn new(
+        tick: GameTime,
+        index: u32,
+        source_identifier: u16,
+        queue_index: u32,
+    ) -> Self {
         Self {
             tick,
             index,
@@ -23,10 +30,9 @@ impl SourcedIndex {
     }
 
     /// This value is the tick at which the command was found while parsing the replay, which
-    /// represents the time in the replay at which it was executed. Because CoH3's engine runs at 8
-    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
-    /// replay began, which will tell you when this command was executed.
-    pub fn tick(&self) -> u32 {
+    /// represents the time in the replay at which it was executed. See `GameTime` for converting
+    /// it to seconds or formatting it like the in-game clock.
+    pub fn tick(&self) -> GameTime {
         self.tick
     }
     /// This value is the index of the command relative to the player who issued the command.
@@ -46,7 +52,8 @@ impl SourcedIndex {
     /// to. Usually used with build and cancellation commands, every time a build command is issued,
     /// the command is added to the source structure's build queue and given an index. These indexes
     /// start at 1 and increase by 1 every time a new build command is issued. This value can be used
-    /// alongside source identifier to determine which specific build command is being cancelled.
+    /// alongside source identifier to determine which specific build command is being cancelled,
+    /// which `Player::production` does for every cancellation in the match.
     pub fn queue_index(&self) -> u32 {
         self.queue_index
     }
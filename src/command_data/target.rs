@@ -0,0 +1,34 @@
+use crate::command_data::Position;
+use crate::data::ticks;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The target of a command that acts on a location or another object, such as a move order.
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub enum Target {
+    /// A point on the map.
+    Position(Position),
+    /// An entity, identified by the internal identifier given to it by the game engine. This
+    /// matches `Targeted::entity_ids` when the entity is also selected.
+    Entity(u32),
+    /// A squad, identified by the internal identifier given to it by the game engine. This matches
+    /// `Targeted::squad_ids` when the squad is also selected.
+    Squad(u32),
+    /// A direction rather than a point, given as a unit vector. Face orders use this when the
+    /// player drags out a facing instead of clicking on a point or object.
+    Direction(Position),
+}
+
+impl Target {
+    pub(crate) fn from_data(target: ticks::Target) -> Self {
+        match target {
+            ticks::Target::Position(x, y, z) => Self::Position(Position::new(x, y, z)),
+            ticks::Target::Entity(id) => Self::Entity(id),
+            ticks::Target::Squad(id) => Self::Squad(id),
+            ticks::Target::Direction(x, y, z) => Self::Direction(Position::new(x, y, z)),
+        }
+    }
+}
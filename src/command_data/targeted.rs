@@ -0,0 +1,109 @@
+use crate::command_data::{Position, Target};
+use crate::data::ticks;
+use crate::game_time::GameTime;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format for orders issued to a selection of squads or entities, with an optional
+/// target such as a location on the map.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct Targeted {
+    tick: GameTime,
+    index: u32,
+    squad_ids: Vec<u32>,
+    entity_ids: Vec<u32>,
+    target: Option<Target>,
+    queued: bool,
+}
+
+impl Targeted {
+    pub(crate) fn new(
+        tick: GameTime,
+        index: u32,
+        selection: Vec<u32>,
+        target: Option<ticks::Target>,
+        queued: bool,
+    ) -> Self {
+        let (squad_ids, entity_ids) = split_selection(selection);
+
+        Self {
+            tick,
+            index,
+            squad_ids,
+            entity_ids,
+            target: target.map(Target::from_data),
+            queued,
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. See `GameTime` for converting
+    /// it to seconds or formatting it like the in-game clock.
+    pub fn tick(&self) -> GameTime {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// The internal identifiers of the squads the command was issued to. These identifiers are
+    /// assigned by the game engine as squads are created, so they can be used to follow a squad
+    /// across commands. They are the same identifiers used by `Target::Squad` and
+    /// `Placement::squad_ids`.
+    pub fn squad_ids(&self) -> &[u32] {
+        &self.squad_ids
+    }
+    /// The internal identifiers of the entities (vehicles, buildings, etc. that aren't part of a
+    /// squad) the command was issued to. These are the same identifiers used by `Target::Entity`.
+    pub fn entity_ids(&self) -> &[u32] {
+        &self.entity_ids
+    }
+    /// What the command was targeting, or `None` if the command has no target (for example a
+    /// retreat order, where the game picks the destination).
+    pub fn target(&self) -> Option<Target> {
+        self.target
+    }
+    /// The world space position the command was targeting, or `None` if the command targeted a
+    /// squad or entity instead of a point on the map.
+    pub fn position(&self) -> Option<Position> {
+        match self.target {
+            Some(Target::Position(position)) => Some(position),
+            _ => None,
+        }
+    }
+    /// Whether the command was queued behind the selection's existing orders (shift-clicked)
+    /// rather than replacing them. The replay format doesn't mark queued orders explicitly, so
+    /// this is inferred from the layout of the command's parameters and should be treated as a
+    /// best-effort hint. Orders issued by AI players are never marked as queued.
+    pub fn queued(&self) -> bool {
+        self.queued
+    }
+}
+
+// selection IDs carry the kind of the selected object in the top byte, and in the rest an ID that
+// is a fixed offset below the one used to target the same object or to name the builders of a
+// placement. the offsets were found by matching selections against targets in the test replays
+const SQUAD_ID_OFFSET: u32 = 50_000;
+const ENTITY_ID_OFFSET: u32 = 1_000_005_000;
+
+pub(crate) fn split_selection(selection: Vec<u32>) -> (Vec<u32>, Vec<u32>) {
+    let (squads, entities): (Vec<u32>, Vec<u32>) =
+        selection.into_iter().partition(|id| id >> 24 == 0x20);
+
+    (
+        squads
+            .into_iter()
+            .map(|id| (id & 0x00ff_ffff) + SQUAD_ID_OFFSET)
+            .collect(),
+        entities
+            .into_iter()
+            .map(|id| (id & 0x00ff_ffff) + ENTITY_ID_OFFSET)
+            .collect(),
+    )
+}
@@ -0,0 +1,84 @@
+use crate::command_data::{targeted::split_selection, Position, Target};
+use crate::data::ticks;
+use crate::game_time::GameTime;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A command format for abilities, upgrades and reinforcements issued by a selection of squads or
+/// entities, with the pbgid of what was used and an optional target such as a location on the
+/// map.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct TargetedPbgid {
+    tick: GameTime,
+    index: u32,
+    pbgid: Option<u32>,
+    squad_ids: Vec<u32>,
+    entity_ids: Vec<u32>,
+    target: Option<Target>,
+}
+
+impl TargetedPbgid {
+    pub(crate) fn new(
+        tick: GameTime,
+        index: u32,
+        selection: Vec<u32>,
+        pbgid: Option<u32>,
+        target: Option<ticks::Target>,
+    ) -> Self {
+        let (squad_ids, entity_ids) = split_selection(selection);
+
+        Self {
+            tick,
+            index,
+            pbgid,
+            squad_ids,
+            entity_ids,
+            target: target.map(Target::from_data),
+        }
+    }
+
+    /// This value is the tick at which the command was found while parsing the replay, which
+    /// represents the time in the replay at which it was executed. See `GameTime` for converting
+    /// it to seconds or formatting it like the in-game clock.
+    pub fn tick(&self) -> GameTime {
+        self.tick
+    }
+    /// This value is the index of the command relative to the player who issued the command.
+    /// Indexes start at 1 and increment on every player-issued command, which means you should be
+    /// able to look at the maximum index value of the commands associated with a player to
+    /// determine how many commands that player issued in a given game.
+    pub fn index(&self) -> u32 {
+        self.index
+    }
+    /// The pbgid of the ability, upgrade or squad blueprint the command used, or `None` if the
+    /// command didn't name one. Context sensitive abilities (for example picking up a weapon or
+    /// garrisoning a building) only give a target, and the game works out the ability from that.
+    pub fn pbgid(&self) -> Option<u32> {
+        self.pbgid
+    }
+    /// The internal identifiers of the squads that issued the command. See `Targeted::squad_ids`.
+    pub fn squad_ids(&self) -> &[u32] {
+        &self.squad_ids
+    }
+    /// The internal identifiers of the entities (vehicles, buildings, etc. that aren't part of a
+    /// squad) that issued the command. See `Targeted::entity_ids`.
+    pub fn entity_ids(&self) -> &[u32] {
+        &self.entity_ids
+    }
+    /// What the command was targeting, or `None` if the command has no target (for example an
+    /// upgrade or reinforcement).
+    pub fn target(&self) -> Option<Target> {
+        self.target
+    }
+    /// The world space position the command was targeting, or `None` if the command targeted a
+    /// squad or entity instead of a point on the map.
+    pub fn position(&self) -> Option<Position> {
+        match self.target {
+            Some(Target::Position(position)) => Some(position),
+            _ => None,
+        }
+    }
+}
@@ -1,20 +1,21 @@
 use crate::command_type::CommandType;
+use crate::game_time::GameTime;
 
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
 /// A catch-all command format to cover commands that aren't currently being parsed.
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 pub struct Unknown {
-    tick: u32,
+    tick: GameTime,
     index: u32,
     action_type: CommandType,
 }
 
 impl Unknown {
-    pub(crate) fn new(tick: u32, index: u32, action_type: CommandType) -> Self {
+    pub(crate) fn new(tick: GameTime, index: u32, action_type: CommandType) -> Self {
         Self {
             tick,
             index,
@@ -23,10 +24,9 @@ impl Unknown {
     }
 
     /// This value is the tick at which the command was found while parsing the replay, which
-    /// represents the time in the replay at which it was executed. Because CoH3's engine runs at 8
-    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
-    /// replay began, which will tell you when this command was executed.
-    pub fn tick(&self) -> u32 {
+    /// represents the time in the replay at which it was executed. See `GameTime` for converting
+    /// it to seconds or formatting it like the in-game clock.
+    pub fn tick(&self) -> GameTime {
         self.tick
     }
     /// This value is the index of the command relative to the player who issued the command.
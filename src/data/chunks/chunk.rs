@@ -1,7 +1,10 @@
 use crate::data::chunks::{
-    DataAutoChunk, DataDataChunk, DataSdscChunk, FoldChunk, Header, TrashDataChunk,
+    DataAutoChunk, DataDataChunk, DataGrifChunk, DataLocsChunk, DataMtypChunk, DataPlasChunk,
+    DataReplChunk, DataSavpChunk, DataSdscChunk, FoldChunk, Header, TrashDataChunk,
 };
-use crate::data::{ParserResult, Span};
+use crate::data::parser::within;
+use crate::data::{ParseError, ParserResult, Span};
+use nom::branch::alt;
 
 #[derive(Debug)]
 pub enum Chunk {
@@ -9,24 +12,57 @@ pub enum Chunk {
     Data(#[allow(unused)] TrashDataChunk),
     DataAuto(DataAutoChunk),
     DataData(DataDataChunk),
+    DataGrif(DataGrifChunk),
+    DataLocs(DataLocsChunk),
+    DataMtyp(DataMtypChunk),
+    DataPlas(DataPlasChunk),
+    DataRepl(DataReplChunk),
+    DataSavp(DataSavpChunk),
     DataSdsc(DataSdscChunk),
 }
 
 impl Chunk {
-    pub fn parse(version: u16) -> impl FnMut(Span) -> ParserResult<Chunk> {
-        move |input: Span| {
-            let (input, header) = Header::parse(input)?;
+    pub fn parse(version: u16, parent: String) -> impl FnMut(Span) -> ParserResult<Chunk> {
+        move |start: Span| {
+            let (input, header) = Header::parse(start)?;
+            let header = header.within(&parent);
 
-            match &header.chunk_kind as &str {
-                "DATA" => match &header.chunk_type as &str {
-                    "AUTO" => DataAutoChunk::parse(input, header),
-                    "DATA" => DataDataChunk::parse(input, header),
-                    "SDSC" => DataSdscChunk::parse(input, header),
-                    _ => TrashDataChunk::parse(input, header),
-                },
-                "FOLD" => FoldChunk::parse(input, header, version),
-                _ => panic!(),
+            if !matches!(&header.chunk_kind as &str, "DATA" | "FOLD") {
+                return Err(nom::Err::Failure(ParseError::chunk_signature(start, 4)));
             }
+
+            within(header.path.clone(), move |input| {
+                Self::parse_body(input, header.clone(), version)
+            })(input)
+        }
+    }
+
+    fn parse_body(input: Span, header: Header, version: u16) -> ParserResult<Chunk> {
+        match (&header.chunk_kind as &str, &header.chunk_type as &str) {
+            ("FOLD", _) => FoldChunk::parse(input, header, version),
+            (_, "AUTO") => DataAutoChunk::parse(input, header),
+            (_, "DATA") => DataDataChunk::parse(input, header),
+            (_, "GRIF") => Self::parse_or_skip(input, header, DataGrifChunk::parse),
+            (_, "LOCS") => Self::parse_or_skip(input, header, DataLocsChunk::parse),
+            (_, "MTYP") => Self::parse_or_skip(input, header, DataMtypChunk::parse),
+            (_, "PLAS") => Self::parse_or_skip(input, header, DataPlasChunk::parse),
+            (_, "REPL") => Self::parse_or_skip(input, header, DataReplChunk::parse),
+            (_, "SAVP") => Self::parse_or_skip(input, header, DataSavpChunk::parse),
+            (_, "SDSC") => DataSdscChunk::parse(input, header),
+            _ => TrashDataChunk::parse(input, header),
         }
     }
+
+    // the layouts of these chunks were worked out from a handful of replays, so rather than failing
+    // the whole replay on a layout we haven't seen, fall back to skipping the chunk like we used to
+    fn parse_or_skip(
+        input: Span,
+        header: Header,
+        parser: fn(Span, Header) -> ParserResult<Chunk>,
+    ) -> ParserResult<Chunk> {
+        alt((
+            |input| parser(input, header.clone()),
+            |input| TrashDataChunk::parse(input, header.clone()),
+        ))(input)
+    }
 }
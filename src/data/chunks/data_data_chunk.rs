@@ -4,7 +4,7 @@ use crate::data::{ParserResult, Player, Span};
 use byteorder::{LittleEndian, ReadBytesExt};
 use nom::bytes::complete::{tag, take, take_while};
 use nom::character::{is_digit, is_hex_digit};
-use nom::combinator::{cut, map, map_parser, peek, verify};
+use nom::combinator::{cut, map, map_parser, map_res, peek, verify};
 use nom::multi::{length_count, length_data, length_value, many_m_n, many_till};
 use nom::number::complete::{le_u32, le_u64};
 use nom::sequence::{separated_pair, tuple};
@@ -13,8 +13,8 @@ use uuid::Uuid;
 
 #[derive(Debug)]
 pub struct Option {
-    _name: String,
-    _value: u32,
+    pub name: String,
+    pub value: u32,
 }
 
 impl Option {
@@ -22,22 +22,19 @@ impl Option {
     pub fn parse_option(input: Span) -> ParserResult<Option> {
         map(
             tuple((parse_utf8_variable(le_u32), le_u32)),
-            |((_, name), value)| Option {
-                _name: name,
-                _value: value,
-            },
+            |((_, name), value)| Option { name, value },
         )(input)
     }
 }
 
 #[derive(Debug)]
 pub struct DataDataChunk {
-    _header: Header,
+    pub header: Header,
     _opponent_type: u32,
     pub players: Vec<Player>,
     pub skirmish: bool,
     pub matchhistory_id: u64,
-    _options: Vec<Option>,
+    pub options: Vec<Option>,
     pub mod_uuid: Uuid,
     _unknown_number: u32,
 }
@@ -77,12 +74,12 @@ impl DataDataChunk {
                     (mod_uuid, unknown_number),
                 )| {
                     DataData(DataDataChunk {
-                        _header: header.clone(),
+                        header: header.clone(),
                         _opponent_type: opponent_type,
                         players,
                         skirmish,
                         matchhistory_id,
-                        _options: options,
+                        options,
                         mod_uuid,
                         _unknown_number: unknown_number,
                     })
@@ -123,16 +120,18 @@ impl DataDataChunk {
     fn parse_mod_info(input: Span) -> ParserResult<(Uuid, u32)> {
         length_value(
             le_u32,
-            map(
+            map_res(
                 separated_pair(take_while(is_hex_digit), tag(":"), take_while(is_digit)),
                 |(mod_uuid, unknown_number): (Span, Span)| {
-                    (
-                        Uuid::try_parse_ascii(&mod_uuid).unwrap(),
-                        unknown_number
-                            .into_fragment()
-                            .read_u32::<LittleEndian>()
-                            .unwrap(),
-                    )
+                    Uuid::try_parse_ascii(&mod_uuid)
+                        .map_err(|_| ())
+                        .and_then(|mod_uuid| {
+                            unknown_number
+                                .into_fragment()
+                                .read_u32::<LittleEndian>()
+                                .map(|unknown_number| (mod_uuid, unknown_number))
+                                .map_err(|_| ())
+                        })
                 },
             ),
         )(input)
@@ -0,0 +1,28 @@
+use crate::data::chunks::header::Header;
+use crate::data::chunks::Chunk;
+use crate::data::chunks::Chunk::DataGrif;
+use crate::data::{ParserResult, Span};
+use nom::bytes::complete::take;
+use nom::combinator::{map, map_parser};
+use nom::number::complete::le_u32;
+use nom::sequence::tuple;
+use nom_tracable::tracable_parser;
+
+// the first value is 2 in local skirmish games and 1 otherwise; the second looks like a hash
+#[derive(Debug)]
+pub struct DataGrifChunk {
+    pub kind: u32,
+    pub hash: u32,
+}
+
+impl DataGrifChunk {
+    #[tracable_parser]
+    pub fn parse(input: Span, header: Header) -> ParserResult<Chunk> {
+        map_parser(
+            take(header.length),
+            map(tuple((le_u32, le_u32)), |(kind, hash)| {
+                DataGrif(DataGrifChunk { kind, hash })
+            }),
+        )(input)
+    }
+}
@@ -0,0 +1,24 @@
+use crate::data::chunks::header::Header;
+use crate::data::chunks::Chunk;
+use crate::data::chunks::Chunk::DataLocs;
+use crate::data::{ParserResult, Span};
+use nom::bytes::complete::take;
+use nom::combinator::{map, map_parser};
+use nom::number::complete::le_u32;
+use nom_tracable::tracable_parser;
+
+// either 0 or 1, meaning unknown
+#[derive(Debug)]
+pub struct DataLocsChunk {
+    pub value: u32,
+}
+
+impl DataLocsChunk {
+    #[tracable_parser]
+    pub fn parse(input: Span, header: Header) -> ParserResult<Chunk> {
+        map_parser(
+            take(header.length),
+            map(le_u32, |value| DataLocs(DataLocsChunk { value })),
+        )(input)
+    }
+}
@@ -0,0 +1,34 @@
+use crate::data::chunks::header::Header;
+use crate::data::chunks::Chunk;
+use crate::data::chunks::Chunk::DataMtyp;
+use crate::data::{ParserResult, Span};
+use nom::bytes::complete::take;
+use nom::combinator::{map, map_parser};
+use nom::number::complete::{le_i64, le_u64};
+use nom::sequence::tuple;
+use nom_tracable::tracable_parser;
+
+#[derive(Debug)]
+pub struct DataMtypChunk {
+    pub matchtype_id: u64,
+    // negative in skirmish games, which aren't tracked by Relic
+    _matchhistory_id: i64,
+}
+
+impl DataMtypChunk {
+    #[tracable_parser]
+    pub fn parse(input: Span, header: Header) -> ParserResult<Chunk> {
+        map_parser(
+            take(header.length),
+            map(
+                tuple((le_u64, le_i64)),
+                |(matchtype_id, matchhistory_id)| {
+                    DataMtyp(DataMtypChunk {
+                        matchtype_id,
+                        _matchhistory_id: matchhistory_id,
+                    })
+                },
+            ),
+        )(input)
+    }
+}
@@ -0,0 +1,34 @@
+use crate::data::chunks::header::Header;
+use crate::data::chunks::Chunk;
+use crate::data::chunks::Chunk::DataPlas;
+use crate::data::{ParserResult, Span};
+use nom::bytes::complete::take;
+use nom::combinator::{map, map_parser};
+use nom::multi::length_count;
+use nom::number::complete::le_u32;
+use nom_tracable::tracable_parser;
+
+// player IDs in this chunk are offset by 1000 from the IDs used in DATADATA and the tick data
+const PLAYER_ID_OFFSET: u32 = 1000;
+
+#[derive(Debug)]
+pub struct DataPlasChunk {
+    pub player_ids: Vec<u32>,
+}
+
+impl DataPlasChunk {
+    #[tracable_parser]
+    pub fn parse(input: Span, header: Header) -> ParserResult<Chunk> {
+        map_parser(
+            take(header.length),
+            map(length_count(le_u32, le_u32), |ids| {
+                DataPlas(DataPlasChunk {
+                    player_ids: ids
+                        .into_iter()
+                        .map(|id| id.saturating_sub(PLAYER_ID_OFFSET))
+                        .collect(),
+                })
+            }),
+        )(input)
+    }
+}
@@ -0,0 +1,30 @@
+use crate::data::chunks::header::Header;
+use crate::data::chunks::Chunk;
+use crate::data::chunks::Chunk::DataRepl;
+use crate::data::{ParserResult, Span};
+use nom::bytes::complete::take;
+use nom::combinator::{map, map_parser};
+use nom::number::complete::le_u32;
+use nom_tracable::tracable_parser;
+
+// uses the same 1000-offset player IDs as DATAPLAS
+const PLAYER_ID_OFFSET: u32 = 1000;
+
+#[derive(Debug)]
+pub struct DataReplChunk {
+    pub player_id: u32,
+}
+
+impl DataReplChunk {
+    #[tracable_parser]
+    pub fn parse(input: Span, header: Header) -> ParserResult<Chunk> {
+        map_parser(
+            take(header.length),
+            map(le_u32, |id: u32| {
+                DataRepl(DataReplChunk {
+                    player_id: id.saturating_sub(PLAYER_ID_OFFSET),
+                })
+            }),
+        )(input)
+    }
+}
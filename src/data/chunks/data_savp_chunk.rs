@@ -0,0 +1,24 @@
+use crate::data::chunks::header::Header;
+use crate::data::chunks::Chunk;
+use crate::data::chunks::Chunk::DataSavp;
+use crate::data::{ParserResult, Span};
+use nom::bytes::complete::take;
+use nom::combinator::{map, map_parser};
+use nom::number::complete::le_u32;
+use nom_tracable::tracable_parser;
+
+// always zero in every replay we've seen
+#[derive(Debug)]
+pub struct DataSavpChunk {
+    pub value: u32,
+}
+
+impl DataSavpChunk {
+    #[tracable_parser]
+    pub fn parse(input: Span, header: Header) -> ParserResult<Chunk> {
+        map_parser(
+            take(header.length),
+            map(le_u32, |value| DataSavp(DataSavpChunk { value })),
+        )(input)
+    }
+}
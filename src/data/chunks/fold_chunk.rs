@@ -2,8 +2,7 @@ use crate::data::chunks::{Chunk, Chunk::Fold, Header};
 use crate::data::{ParserResult, Span};
 use nom::bytes::complete::take;
 use nom::combinator::{cut, eof, map, map_parser};
-use nom::multi::many0;
-use nom::sequence::terminated;
+use nom::multi::many_till;
 
 #[derive(Debug)]
 pub struct FoldChunk {
@@ -15,17 +14,14 @@ impl FoldChunk {
     pub fn parse(input: Span, header: Header, version: u16) -> ParserResult<Chunk> {
         cut(map_parser(
             take(header.length),
-            terminated(
-                map(
-                    map_parser(take(header.length), many0(Chunk::parse(version))),
-                    move |chunks| {
-                        Fold(FoldChunk {
-                            _header: header.clone(),
-                            chunks,
-                        })
-                    },
-                ),
-                eof,
+            map(
+                many_till(Chunk::parse(version, header.path.clone()), eof),
+                move |(chunks, _)| {
+                    Fold(FoldChunk {
+                        _header: header.clone(),
+                        chunks,
+                    })
+                },
             ),
         ))(input)
     }
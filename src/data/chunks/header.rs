@@ -1,4 +1,4 @@
-use crate::data::parser::parse_utf8_fixed;
+use crate::data::parser::{parse_utf8_fixed, signature};
 use crate::data::{ParserResult, Span};
 use nom::branch::alt;
 use nom::bytes::complete::tag;
@@ -14,6 +14,7 @@ pub struct Header {
     pub version: u32,
     pub length: u32,
     _name_length: u32,
+    pub path: String,
 }
 
 impl Header {
@@ -35,15 +36,32 @@ impl Header {
                 version,
                 length,
                 _name_length: name_length,
+                path: String::new(),
             },
         )(input)
     }
 
+    pub fn within(self, parent: &str) -> Header {
+        let name = format!("{}{}", self.chunk_kind, self.chunk_type);
+
+        Header {
+            path: if parent.is_empty() {
+                name
+            } else {
+                format!("{}/{}", parent, name)
+            },
+            ..self
+        }
+    }
+
     #[tracable_parser]
     fn parse_chunk_kind(input: Span) -> ParserResult<String> {
-        map(alt((tag("DATA"), tag("FOLD"))), |s: Span| {
-            String::from_utf8_lossy(s.fragment()).into_owned()
-        })(input)
+        signature(
+            4,
+            map(alt((tag("DATA"), tag("FOLD"))), |s: Span| {
+                String::from_utf8_lossy(s.fragment()).into_owned()
+            }),
+        )(input)
     }
 
     #[tracable_parser]
@@ -1,6 +1,12 @@
 mod chunk;
 mod data_auto_chunk;
 mod data_data_chunk;
+mod data_grif_chunk;
+mod data_locs_chunk;
+mod data_mtyp_chunk;
+mod data_plas_chunk;
+mod data_repl_chunk;
+mod data_savp_chunk;
 mod data_sdsc_chunk;
 mod fold_chunk;
 mod header;
@@ -9,6 +15,12 @@ mod trash_data_chunk;
 pub use crate::data::chunks::chunk::Chunk;
 pub use crate::data::chunks::data_auto_chunk::DataAutoChunk;
 pub use crate::data::chunks::data_data_chunk::DataDataChunk;
+pub use crate::data::chunks::data_grif_chunk::DataGrifChunk;
+pub use crate::data::chunks::data_locs_chunk::DataLocsChunk;
+pub use crate::data::chunks::data_mtyp_chunk::DataMtypChunk;
+pub use crate::data::chunks::data_plas_chunk::DataPlasChunk;
+pub use crate::data::chunks::data_repl_chunk::DataReplChunk;
+pub use crate::data::chunks::data_savp_chunk::DataSavpChunk;
 pub use crate::data::chunks::data_sdsc_chunk::DataSdscChunk;
 use crate::data::chunks::fold_chunk::FoldChunk;
 use crate::data::chunks::header::Header;
@@ -1,4 +1,4 @@
-use crate::data::parser::verify_le_u32;
+use crate::data::parser::{signature, verify_le_u32};
 use crate::data::{ParserResult, Span};
 use nom::bytes::complete::tag;
 use nom::combinator::map;
@@ -34,9 +34,12 @@ impl Chunky {
 
     #[tracable_parser]
     fn parse_name(input: Span) -> ParserResult<String> {
-        map(tag("Relic Chunky"), |s: Span| {
-            String::from_utf8_lossy(s.fragment()).into_owned()
-        })(input)
+        signature(
+            12,
+            map(tag("Relic Chunky"), |s: Span| {
+                String::from_utf8_lossy(s.fragment()).into_owned()
+            }),
+        )(input)
     }
 
     #[tracable_parser]
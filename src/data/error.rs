@@ -0,0 +1,68 @@
+use crate::data::Span;
+use nom::error::{ErrorKind, FromExternalError, ParseError as NomParseError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    Nom(ErrorKind),
+    ChunkSignature(String),
+}
+
+#[derive(Debug)]
+pub struct ParseError {
+    pub offset: usize,
+    pub kind: ParseErrorKind,
+    pub path: String,
+}
+
+impl ParseError {
+    pub fn chunk_signature(input: Span, len: usize) -> Self {
+        let len = len.min(input.len());
+
+        Self {
+            offset: input.location_offset(),
+            kind: ParseErrorKind::ChunkSignature(
+                String::from_utf8_lossy(&input.fragment()[..len]).into_owned(),
+            ),
+            path: String::new(),
+        }
+    }
+
+    pub fn within(self, path: &str) -> Self {
+        if self.path.is_empty() {
+            Self {
+                path: path.to_string(),
+                ..self
+            }
+        } else {
+            self
+        }
+    }
+
+    pub fn is_truncation(&self) -> bool {
+        matches!(
+            self.kind,
+            ParseErrorKind::Nom(ErrorKind::Eof) | ParseErrorKind::Nom(ErrorKind::Complete)
+        )
+    }
+}
+
+impl<'a> NomParseError<Span<'a>> for ParseError {
+    fn from_error_kind(input: Span<'a>, kind: ErrorKind) -> Self {
+        Self {
+            offset: input.location_offset(),
+            kind: ParseErrorKind::Nom(kind),
+            path: String::new(),
+        }
+    }
+
+    // keep the innermost error, since that's the one that points at the offending bytes
+    fn append(_input: Span<'a>, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<'a, E> FromExternalError<Span<'a>, E> for ParseError {
+    fn from_external_error(input: Span<'a>, kind: ErrorKind, _e: E) -> Self {
+        Self::from_error_kind(input, kind)
+    }
+}
@@ -1,33 +1,104 @@
+use crate::data::parser::parse_utf8_variable;
 use crate::data::{ParserResult, Span};
 use nom::bytes::complete::take;
-use nom::combinator::{cut, map};
+use nom::combinator::{cut, map, map_parser, opt, rest};
 use nom::multi::length_data;
-use nom::number::complete::le_u32;
+use nom::number::complete::{le_u32, le_u64, le_u8};
 use nom::sequence::tuple;
 use nom_tracable::tracable_parser;
 
 #[derive(Debug)]
 pub struct Item {
-    _data: Vec<u8>,
+    pub id: u64,
+    pub details: Option<ItemDetails>,
+}
+
+// only present on items belonging to human players, who own server-side instances of their items
+#[derive(Debug)]
+pub struct ItemDetails {
+    pub instance_id: u64,
+    pub profile_id: u64,
+    pub durability: u32,
+    pub durability_type: u32,
+    pub metadata: String,
+    pub timestamp: u64,
+    pub location_id: u64,
 }
 
 impl Item {
+    // items are length-prefixed, so one whose data doesn't match the known layout can be skipped
+    // without losing track of the items that follow it
     #[tracable_parser]
-    pub fn parse_player_item(input: Span) -> ParserResult<Item> {
+    pub fn parse_player_item(input: Span) -> ParserResult<Option<Item>> {
         cut(map(
-            tuple((take(24u32), length_data(le_u32), take(4u32))),
-            |(_, data, _): (Span, Span, Span)| Item {
-                _data: data.to_vec(),
+            tuple((
+                take(24u32),
+                map_parser(length_data(le_u32), opt(Self::parse_player_item_data)),
+                take(4u32),
+            )),
+            |(_, item, _)| {
+                item.map(|(id, details)| Item {
+                    id,
+                    details: Some(details),
+                })
             },
         ))(input)
     }
 
     #[tracable_parser]
-    pub fn parse_cpu_item(input: Span) -> ParserResult<Item> {
+    fn parse_player_item_data(input: Span) -> ParserResult<(u64, ItemDetails)> {
+        map(
+            tuple((
+                le_u64,
+                le_u64,
+                le_u64,
+                le_u64,
+                le_u32,
+                le_u32,
+                le_u8,
+                parse_utf8_variable(le_u32),
+                le_u64,
+                le_u64,
+                rest,
+            )),
+            |(
+                instance_id,
+                _,
+                id,
+                profile_id,
+                durability,
+                durability_type,
+                _,
+                (_, metadata),
+                timestamp,
+                location_id,
+                _,
+            )| {
+                (
+                    id,
+                    ItemDetails {
+                        instance_id,
+                        profile_id,
+                        durability,
+                        durability_type,
+                        metadata,
+                        timestamp,
+                        location_id,
+                    },
+                )
+            },
+        )(input)
+    }
+
+    #[tracable_parser]
+    pub fn parse_cpu_item(input: Span) -> ParserResult<Option<Item>> {
         cut(map(
-            tuple((take(8u32), take(4u32))),
-            |(data, _): (Span, Span)| Item {
-                _data: data.to_vec(),
+            tuple((take(4u32), le_u32, take(4u32))),
+            |(_, pbgid, _): (Span, u32, Span)| {
+                Some(Item {
+                    id: pbgid as u64,
+                    details: None,
+                })
             },
         ))(input)
     }
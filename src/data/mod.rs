@@ -4,6 +4,7 @@ use nom_tracable::TracableInfo;
 
 pub mod chunks;
 mod chunky;
+mod error;
 mod header;
 mod item;
 mod parser;
@@ -12,11 +13,12 @@ mod replay;
 pub mod ticks;
 
 use crate::data::chunky::Chunky;
+pub use crate::data::error::{ParseError, ParseErrorKind};
 use crate::data::header::Header;
-use crate::data::item::Item;
+pub use crate::data::item::Item;
 pub use crate::data::player::Player;
 pub use crate::data::replay::Replay;
 
 pub type Span<'a> = LocatedSpan<&'a [u8], TracableInfo>;
 
-pub type ParserResult<'a, T> = IResult<Span<'a>, T>;
+pub type ParserResult<'a, T> = IResult<Span<'a>, T, ParseError>;
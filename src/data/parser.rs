@@ -1,19 +1,19 @@
-use crate::data::Span;
+use crate::data::{ParseError as SpanError, ParserResult, Span};
 use byteorder::{LittleEndian, ReadBytesExt};
 use nom::bytes::complete::{take, take_while};
 use nom::combinator::{map, peek, verify};
-use nom::error::ParseError;
+use nom::error::{ErrorKind, ParseError};
 use nom::multi::many_till;
 use nom::number::complete::{le_u16, le_u32};
-use nom::{IResult, ToUsize};
+use nom::{Err, IResult, ToUsize};
 use std::io::Cursor;
 use std::string::String;
 
-pub fn verify_zero_u16(input: Span) -> IResult<Span, u16> {
+pub fn verify_zero_u16(input: Span) -> ParserResult<u16> {
     verify(le_u16, |n: &u16| *n == 0)(input)
 }
 
-pub fn verify_le_u32<'a>(expected: u32) -> impl FnMut(Span<'a>) -> IResult<Span<'a>, u32> {
+pub fn verify_le_u32<'a>(expected: u32) -> impl FnMut(Span<'a>) -> ParserResult<'a, u32> {
     verify(le_u32, move |n: &u32| *n == expected)
 }
 
@@ -56,7 +56,7 @@ fn bytes_to_utf16(bytes: Span) -> String {
     String::from_utf16_lossy(&u16_vec)
 }
 
-pub fn parse_utf16_terminated(input: Span) -> IResult<Span, String> {
+pub fn parse_utf16_terminated(input: Span) -> ParserResult<String> {
     map(
         many_till(le_u16, peek(verify(le_u16, |n: &u16| *n == 0))),
         |(u16s, _)| String::from_utf16_lossy(&u16s),
@@ -90,6 +90,41 @@ where
     }
 }
 
-pub fn take_zeroes(input: Span) -> IResult<Span, Span> {
+pub fn take_zeroes(input: Span) -> ParserResult<Span> {
     take_while(|n: u8| n == 0)(input)
 }
+
+pub fn within<'a, O, F>(
+    path: impl Into<String>,
+    mut f: F,
+) -> impl FnMut(Span<'a>) -> ParserResult<'a, O>
+where
+    F: FnMut(Span<'a>) -> ParserResult<'a, O>,
+{
+    let path = path.into();
+
+    move |input: Span<'a>| match f(input) {
+        Err(Err::Incomplete(_)) => Err(Err::Failure(
+            SpanError::from_error_kind(input, ErrorKind::Complete).within(&path),
+        )),
+        Err(err) => Err(err.map(|err| err.within(&path))),
+        Ok(res) => Ok(res),
+    }
+}
+
+pub fn signature<'a, O, F>(len: usize, mut f: F) -> impl FnMut(Span<'a>) -> ParserResult<'a, O>
+where
+    F: FnMut(Span<'a>) -> ParserResult<'a, O>,
+{
+    move |input: Span<'a>| {
+        f(input).map_err(|err| {
+            err.map(|err| {
+                if input.len() < len || err.is_truncation() {
+                    SpanError::from_error_kind(input, ErrorKind::Eof)
+                } else {
+                    SpanError::chunk_signature(input, len)
+                }
+            })
+        })
+    }
+}
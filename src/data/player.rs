@@ -5,8 +5,7 @@ use nom::bytes::complete::take;
 use nom::combinator::{cut, map};
 use nom::multi::length_count;
 use nom::number::complete::{le_u32, le_u64, le_u8};
-use nom::sequence::tuple;
-use nom::IResult;
+use nom::sequence::{preceded, tuple};
 use nom_tracable::tracable_parser;
 
 #[derive(Debug)]
@@ -16,14 +15,18 @@ pub struct Player {
     pub name: String,
     pub team: u32,
     pub faction: String,
-    _ai_type: String,
+    pub ai_difficulty: u32,
+    pub ai_type: String,
     pub steam_id: String,
     pub profile_id: u64,
-    _items: Vec<Item>,
+    pub battlegroup_items: Vec<Item>,
+    pub cosmetic_items: Vec<Item>,
+    pub offset: usize,
 }
 
 impl Player {
     pub fn parse_player(input: Span) -> ParserResult<Player> {
+        let offset = input.location_offset();
         let (input, player) = cut(map(
             tuple((
                 le_u8,
@@ -32,7 +35,7 @@ impl Player {
                 le_u32,
                 take(1u32),
                 Self::parse_faction,
-                take(8u32),
+                Self::parse_ai_difficulty,
                 Self::parse_ai,
                 take(40u32),
                 le_u64,
@@ -40,26 +43,44 @@ impl Player {
                 Self::parse_steam_id,
                 take(18u32),
             )),
-            |(human, name, team, id, _, faction, _, ai_type, _, profile_id, _, steam_id, _)| {
+            |(
+                human,
+                name,
+                team,
+                id,
+                _,
+                faction,
+                ai_difficulty,
+                ai_type,
+                _,
+                profile_id,
+                _,
+                steam_id,
+                _,
+            )| {
                 Player {
                     id,
                     human,
                     name,
                     team,
                     faction,
-                    _ai_type: ai_type,
+                    ai_difficulty,
+                    ai_type,
                     steam_id,
                     profile_id,
-                    _items: vec![],
+                    battlegroup_items: vec![],
+                    cosmetic_items: vec![],
+                    offset,
                 }
             },
         ))(input)?;
 
-        let (input, items) = Self::parse_items(input, &player)?;
+        let (input, (battlegroup_items, cosmetic_items)) = Self::parse_items(input, &player)?;
         Ok((
             input,
             Player {
-                _items: items,
+                battlegroup_items,
+                cosmetic_items,
                 ..player
             },
         ))
@@ -80,6 +101,10 @@ impl Player {
         Ok((input, faction))
     }
     #[tracable_parser]
+    fn parse_ai_difficulty(input: Span) -> ParserResult<u32> {
+        preceded(take(4u32), le_u32)(input)
+    }
+    #[tracable_parser]
     fn parse_ai(input: Span) -> ParserResult<String> {
         let (input, (_, ai)) = parse_utf8_variable(le_u32)(input)?;
         Ok((input, ai))
@@ -90,7 +115,7 @@ impl Player {
         Ok((input, steam_id))
     }
 
-    fn item_parser_for(player: &Player) -> impl FnMut(Span) -> ParserResult<Item> {
+    fn item_parser_for(player: &Player) -> impl FnMut(Span) -> ParserResult<Option<Item>> {
         if player.human == 0 {
             Item::parse_cpu_item
         } else {
@@ -99,16 +124,21 @@ impl Player {
     }
 
     #[tracable_parser]
-    fn parse_items<'a>(input: Span<'a>, player: &Player) -> IResult<Span<'a>, Vec<Item>> {
+    fn parse_items<'a>(
+        input: Span<'a>,
+        player: &Player,
+    ) -> ParserResult<'a, (Vec<Item>, Vec<Item>)> {
         cut(map(
             tuple((
                 length_count(le_u32, Self::item_parser_for(player)),
                 take(4u32),
                 length_count(le_u32, Self::item_parser_for(player)),
             )),
-            |(mut battlegroup_items, _, mut cosmetic_items)| {
-                battlegroup_items.append(&mut cosmetic_items);
-                battlegroup_items
+            |(battlegroup_items, _, cosmetic_items)| {
+                (
+                    battlegroup_items.into_iter().flatten().collect(),
+                    cosmetic_items.into_iter().flatten().collect(),
+                )
             },
         ))(input)
     }
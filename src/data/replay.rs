@@ -1,12 +1,19 @@
 use crate::command::Command;
-use crate::data::chunks::Chunk::{DataAuto, DataData, DataSdsc};
-use crate::data::chunks::{Chunk, DataAutoChunk, DataDataChunk, DataSdscChunk};
+use crate::data::chunks::Chunk::{
+    DataAuto, DataData, DataGrif, DataLocs, DataMtyp, DataPlas, DataRepl, DataSavp, DataSdsc,
+};
+use crate::data::chunks::{
+    Chunk, DataAutoChunk, DataDataChunk, DataGrifChunk, DataLocsChunk, DataMtypChunk,
+    DataPlasChunk, DataReplChunk, DataSavpChunk, DataSdscChunk,
+};
+use crate::data::parser::within;
 use crate::data::ticks::{CommandTick, Tick};
 use crate::data::{Chunky, Header};
 use crate::data::{ParserResult, Span};
+use crate::errors::VaultError;
+use crate::game_time::GameTime;
 use crate::Message;
-use nom::combinator::eof;
-use nom::combinator::map;
+use nom::combinator::{eof, map};
 use nom::multi::many_till;
 use nom::sequence::tuple;
 use nom_tracable::tracable_parser;
@@ -20,41 +27,52 @@ pub struct Replay {
     pub header: Header,
     _chunkies: Vec<Chunky>,
     pub chunks: Vec<Chunk>,
+    pub chunks_end: usize,
     pub ticks: Vec<Tick>,
 }
 
 impl Replay {
     #[tracable_parser]
     pub fn from_span(input: Span) -> ParserResult<Replay> {
-        let (input, header) = Header::parse_header(input)?;
+        let (input, replay) = Self::parse_chunks(input)?;
+        let (input, (ticks, _)) = within("TICKS", many_till(Tick::parse, eof))(input)?;
 
-        let mut parser = map(
+        Ok((input, Replay { ticks, ..replay }))
+    }
+
+    // parses everything up to the start of the tick data, leaving ticks empty
+    #[tracable_parser]
+    pub fn parse_chunks(input: Span) -> ParserResult<Replay> {
+        let (input, header) = within("HEADER", Header::parse_header)(input)?;
+
+        let (input, (first_chunky, foldpost_chunk, second_chunky, foldinfo_chunk, datasdsc_chunk)) =
             tuple((
-                Chunky::parse,
-                Chunk::parse(header.version),
-                Chunky::parse,
-                Chunk::parse(header.version),
-                Chunk::parse(header.version),
-                many_till(Tick::parse, eof),
-            )),
-            |(
-                first_chunky,
-                foldpost_chunk,
-                second_chunky,
-                foldinfo_chunk,
-                datasdsc_chunk,
-                (ticks, _),
-            )| {
-                Replay {
-                    header: header.clone(),
-                    _chunkies: vec![first_chunky, second_chunky],
-                    chunks: vec![foldpost_chunk, foldinfo_chunk, datasdsc_chunk],
-                    ticks,
-                }
+                within("CHUNKY", Chunky::parse),
+                Chunk::parse(header.version, String::new()),
+                within("CHUNKY", Chunky::parse),
+                Chunk::parse(header.version, String::new()),
+                Chunk::parse(header.version, String::new()),
+            ))(input)?;
+
+        let chunks_end = input.location_offset();
+
+        Ok((
+            input,
+            Replay {
+                header,
+                _chunkies: vec![first_chunky, second_chunky],
+                chunks: vec![foldpost_chunk, foldinfo_chunk, datasdsc_chunk],
+                chunks_end,
+                ticks: vec![],
             },
-        );
+        ))
+    }
 
-        parser(input)
+    #[tracable_parser]
+    pub fn count_command_ticks(input: Span) -> ParserResult<usize> {
+        map(within("TICKS", many_till(Tick::skip, eof)), |(ticks, _)| {
+            ticks.into_iter().filter(|&command| command).count()
+        })(input)
     }
 
     pub fn data_chunks(&self) -> Vec<&Chunk> {
@@ -67,43 +85,93 @@ impl Replay {
             .collect()
     }
 
-    pub fn game_data(&self) -> &DataDataChunk {
-        let chunks = self.data_chunks();
+    pub fn game_data(&self) -> Result<&DataDataChunk, VaultError> {
+        self.data_chunks()
+            .into_iter()
+            .find_map(|chunk| match chunk {
+                DataData(data) => Some(data),
+                _ => None,
+            })
+            .ok_or_else(|| self.missing_chunk("FOLDINFO/DATADATA"))
+    }
 
-        let data_chunk = chunks
-            .iter()
-            .find(|chunk| matches!(chunk, DataData(_)))
-            .unwrap();
+    pub fn automatch_data(&self) -> Option<&DataAutoChunk> {
+        self.data_chunks()
+            .into_iter()
+            .find_map(|chunk| match chunk {
+                DataAuto(chunk) => Some(chunk),
+                _ => None,
+            })
+    }
 
-        match data_chunk {
-            DataData(data) => data,
-            _ => panic!(),
-        }
+    pub fn player_list_data(&self) -> Option<&DataPlasChunk> {
+        self.data_chunks()
+            .into_iter()
+            .find_map(|chunk| match chunk {
+                DataPlas(chunk) => Some(chunk),
+                _ => None,
+            })
     }
 
-    pub fn automatch_data(&self) -> Option<&DataAutoChunk> {
-        match self
-            .data_chunks()
-            .iter()
-            .find(|chunk| matches!(chunk, DataAuto(_)))
-        {
-            Some(DataAuto(chunk)) => Some(chunk),
-            None => None,
-            _ => panic!(),
-        }
+    pub fn match_type_data(&self) -> Option<&DataMtypChunk> {
+        self.data_chunks()
+            .into_iter()
+            .find_map(|chunk| match chunk {
+                DataMtyp(chunk) => Some(chunk),
+                _ => None,
+            })
+    }
+
+    pub fn recorder_data(&self) -> Option<&DataReplChunk> {
+        self.data_chunks()
+            .into_iter()
+            .find_map(|chunk| match chunk {
+                DataRepl(chunk) => Some(chunk),
+                _ => None,
+            })
     }
 
-    pub fn map_data(&self) -> &DataSdscChunk {
-        let chunks = self.data_chunks();
+    pub fn map_grid_data(&self) -> Option<&DataGrifChunk> {
+        self.data_chunks()
+            .into_iter()
+            .find_map(|chunk| match chunk {
+                DataGrif(chunk) => Some(chunk),
+                _ => None,
+            })
+    }
 
-        let map_chunk = chunks
-            .iter()
-            .find(|chunk| matches!(chunk, DataSdsc(_)))
-            .unwrap();
+    pub fn save_parameter_data(&self) -> Option<&DataSavpChunk> {
+        self.data_chunks()
+            .into_iter()
+            .find_map(|chunk| match chunk {
+                DataSavp(chunk) => Some(chunk),
+                _ => None,
+            })
+    }
+
+    pub fn locale_data(&self) -> Option<&DataLocsChunk> {
+        self.data_chunks()
+            .into_iter()
+            .find_map(|chunk| match chunk {
+                DataLocs(chunk) => Some(chunk),
+                _ => None,
+            })
+    }
+
+    pub fn map_data(&self) -> Result<&DataSdscChunk, VaultError> {
+        self.data_chunks()
+            .into_iter()
+            .find_map(|chunk| match chunk {
+                DataSdsc(map) => Some(map),
+                _ => None,
+            })
+            .ok_or_else(|| self.missing_chunk("DATASDSC"))
+    }
 
-        match map_chunk {
-            DataSdsc(map) => map,
-            _ => panic!(),
+    fn missing_chunk(&self, path: &str) -> VaultError {
+        VaultError::MissingChunk {
+            offset: self.chunks_end,
+            path: path.to_string(),
         }
     }
 
@@ -114,21 +182,22 @@ impl Replay {
         })
     }
 
-    pub fn commands(&self) -> HashMap<u32, Vec<Command>> {
-        self.command_ticks()
-            .enumerate()
-            .fold(HashMap::new(), |mut acc, (idx, tick)| {
+    pub fn commands(&self) -> Result<HashMap<u32, Vec<Command>>, VaultError> {
+        self.command_ticks().enumerate().try_fold(
+            HashMap::new(),
+            |mut acc: HashMap<_, Vec<_>>, (idx, tick)| {
                 for bundle in &tick.bundles {
                     for command in &bundle.commands {
                         let player_commands = acc.entry(command.player_id as u32).or_default();
                         player_commands.push(Command::from_data_command_at_tick(
                             command.clone(),
                             idx as u32 + 1,
-                        ));
+                        )?);
                     }
                 }
-                acc
-            })
+                Ok(acc)
+            },
+        )
     }
 
     #[cfg(feature = "raw")]
@@ -149,18 +218,26 @@ impl Replay {
             })
     }
 
+    // messages are timed by the number of command ticks before them, so they share a clock with
+    // commands (message ticks don't advance game time)
     pub fn messages(&self) -> HashMap<String, Vec<Message>> {
+        let mut command_ticks: u32 = 0;
         self.ticks
             .iter()
-            .enumerate()
-            .filter_map(|(idx, tick)| match tick {
-                Tick::Message(message) => Some((idx + 1, message.messages.clone())),
-                _ => None,
+            .filter_map(|tick| match tick {
+                Tick::Command(_) => {
+                    command_ticks += 1;
+                    None
+                }
+                Tick::Message(message) => Some((command_ticks, message.messages.clone())),
             })
             .fold(HashMap::new(), |mut acc, (tick, messages)| {
                 for message in messages.iter() {
                     let msgs = acc.entry(message.name.clone()).or_default();
-                    msgs.push(Message::new(tick as u32, message.message.clone()));
+                    msgs.push(Message::new(
+                        GameTime::from_ticks(tick),
+                        message.message.clone(),
+                    ));
                 }
                 acc
             })
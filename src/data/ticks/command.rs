@@ -3,23 +3,39 @@ use crate::{
     data::{ParserResult, Span},
 };
 use nom::{
+    branch::alt,
     bytes::complete::take,
-    combinator::{flat_map, map, peek, rest},
-    multi::length_value,
-    number::complete::{le_u16, le_u32, le_u8},
-    sequence::tuple,
+    combinator::{complete, flat_map, map, opt, peek, rest, verify},
+    multi::{count, length_count, length_data, length_value},
+    number::complete::{be_u32, le_f32, le_u16, le_u32, le_u8},
+    sequence::{preceded, tuple},
 };
 
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub enum CommandData {
     Empty,
     Pbgid(u32),
     SourcedPbgid(u32, u16),
     Sourced(u16),
     SourcedIndex(u16, u32),
+    Targeted(Vec<u32>, Option<Target>, bool),
+    TargetedPbgid(Vec<u32>, Option<u32>, Option<Target>),
+    SelectedIndex(Vec<u32>, u32),
+    Placement(u32, Vector, Vector, Vector, Vec<u32>),
+    Parameters(Vec<u8>),
     Unknown,
 }
 
+pub type Vector = (f32, f32, f32);
+
+#[derive(Debug, Copy, Clone)]
+pub enum Target {
+    Position(f32, f32, f32),
+    Entity(u32),
+    Squad(u32),
+    Direction(f32, f32, f32),
+}
+
 impl CommandData {
     pub fn parse_empty(input: Span) -> ParserResult<CommandData> {
         map(rest, |_| CommandData::Empty)(input)
@@ -53,6 +69,216 @@ impl CommandData {
         )(input)
     }
 
+    // squad commands refer to their build queue through the selection rather than a source
+    // identifier. falls back to unknown since only cancellations of a single squad's upgrades have
+    // been seen so far
+    pub fn parse_selected_index(input: Span) -> ParserResult<CommandData> {
+        alt((
+            map(
+                tuple((
+                    Self::parse_selection,
+                    preceded(
+                        tuple((
+                            verify(le_u8, |flags| *flags == 0x05),
+                            verify(le_u8, |length| *length == 4),
+                        )),
+                        le_u32,
+                    ),
+                )),
+                |(selection, queue_index)| CommandData::SelectedIndex(selection, queue_index),
+            ),
+            Self::parse_unknown,
+        ))(input)
+    }
+
+    // falls back to unknown since not every variant of these commands has been mapped out yet
+    pub fn parse_targeted(input: Span) -> ParserResult<CommandData> {
+        alt((
+            map(
+                tuple((Self::parse_selection, Self::parse_target_params)),
+                |(selection, (target, queued))| CommandData::Targeted(selection, target, queued),
+            ),
+            Self::parse_unknown,
+        ))(input)
+    }
+
+    // orders such as facing or loading into a transport always use the layout of queued orders,
+    // so they're never marked as queued
+    pub fn parse_unqueued_targeted(input: Span) -> ParserResult<CommandData> {
+        alt((
+            map(
+                tuple((Self::parse_selection, Self::parse_target_params)),
+                |(selection, (target, _))| CommandData::Targeted(selection, target, false),
+            ),
+            Self::parse_unknown,
+        ))(input)
+    }
+
+    // selection IDs have the kind of the selected object (0x10 for entities, 0x20 for squads) in
+    // the top byte. a single selection is stored big endian, while lists are little endian
+    fn parse_selection(input: Span) -> ParserResult<Vec<u32>> {
+        preceded(
+            take(20u32),
+            alt((
+                map(verify(le_u8, |flag| *flag == 0), |_| Vec::new()),
+                map(verify(be_u32, |id| matches!(id >> 24, 0x10 | 0x20)), |id| {
+                    vec![id]
+                }),
+                flat_map(verify(le_u8, |flag| *flag >= 0x40), |flag| {
+                    count(le_u32, (flag - 0x40) as usize)
+                }),
+            )),
+        )(input)
+    }
+
+    // queued orders carry the target directly, while single orders prefix it with 4 bytes.
+    // AI orders use their own layouts, which don't seem to distinguish between the two
+    fn parse_target_params(input: Span) -> ParserResult<(Option<Target>, bool)> {
+        alt((
+            map(verify(le_u8, |flags| *flags == 0xff), |_| (None, false)),
+            flat_map(le_u8, |flags| {
+                let padding = match flags {
+                    0x06 => 4u32,
+                    0x0f => 8u32,
+                    _ => 0u32,
+                };
+
+                map(
+                    length_value(le_u8, preceded(take(padding), Self::parse_target)),
+                    move |target| (Some(target), flags == 0x01),
+                )
+            }),
+        ))(input)
+    }
+
+    // falls back to unknown since some of these commands identify the ability by something other
+    // than a pbgid, and those haven't been mapped out yet
+    pub fn parse_targeted_pbgid(input: Span) -> ParserResult<CommandData> {
+        alt((
+            map(
+                tuple((Self::parse_selection, Self::parse_pbgid_params)),
+                |(selection, (pbgid, target))| CommandData::TargetedPbgid(selection, pbgid, target),
+            ),
+            Self::parse_unknown,
+        ))(input)
+    }
+
+    // a flags value of 0x01 means the parameters are just a target, as with some context
+    // sensitive squad abilities. otherwise the parameters start with the pbgid, and depending on
+    // the flags are followed by a few unknown bytes and the target
+    fn parse_pbgid_params(input: Span) -> ParserResult<(Option<u32>, Option<Target>)> {
+        alt((
+            map(verify(le_u8, |flags| *flags == 0xff), |_| (None, None)),
+            map(
+                preceded(
+                    verify(le_u8, |flags| *flags == 0x01),
+                    length_value(le_u8, Self::parse_target),
+                ),
+                |target| (None, Some(target)),
+            ),
+            flat_map(le_u8, |flags| {
+                let padding = match flags {
+                    0x24 => 6u32,
+                    0x29 => 1u32,
+                    _ => 0u32,
+                };
+
+                map(
+                    length_value(
+                        le_u8,
+                        tuple((
+                            preceded(verify(le_u8, |kind| *kind == 0x01), le_u32),
+                            preceded(take(padding), opt(Self::parse_target)),
+                        )),
+                    ),
+                    |(pbgid, target)| (Some(pbgid), target),
+                )
+            }),
+        ))(input)
+    }
+
+    fn parse_target(input: Span) -> ParserResult<Target> {
+        alt((
+            map(
+                preceded(verify(le_u8, |kind| *kind == 0x02), Self::parse_vector),
+                |(x, y, z)| Target::Position(x, y, z),
+            ),
+            map(
+                preceded(verify(le_u8, |kind| *kind == 0x03), le_u32),
+                Target::Entity,
+            ),
+            map(
+                preceded(verify(le_u8, |kind| *kind == 0x04), le_u32),
+                Target::Squad,
+            ),
+            // directions are a unit vector followed by a value that has always been 1.0
+            map(
+                preceded(
+                    verify(le_u8, |kind| *kind == 0x05),
+                    tuple((Self::parse_vector, le_f32)),
+                ),
+                |((x, y, z), _)| Target::Direction(x, y, z),
+            ),
+        ))(input)
+    }
+
+    fn parse_vector(input: Span) -> ParserResult<Vector> {
+        tuple((le_f32, le_f32, le_f32))(input)
+    }
+
+    // placements store the start and end of the placed line (the same point unless placing
+    // something like a wall), followed by a point one unit in front of the start to give the
+    // facing. some placements identify the blueprint by something other than a pbgid, and those
+    // fall back to unknown
+    pub fn parse_placement(input: Span) -> ParserResult<CommandData> {
+        alt((
+            map(
+                preceded(
+                    take(25u32),
+                    length_value(
+                        le_u8,
+                        tuple((
+                            preceded(verify(le_u8, |kind| *kind == 0x01), le_u32),
+                            Self::parse_vector,
+                            Self::parse_vector,
+                            Self::parse_vector,
+                            preceded(take(7u32), length_count(le_u8, le_u32)),
+                        )),
+                    ),
+                ),
+                |(pbgid, start, end, facing, builders)| {
+                    CommandData::Placement(pbgid, start, end, facing, builders)
+                },
+            ),
+            Self::parse_unknown,
+        ))(input)
+    }
+
+    // keeps the raw parameters of player commands whose layout hasn't been mapped out, which is
+    // either 0xff for no parameters or a flags byte followed by length-prefixed data
+    pub fn parse_parameters(input: Span) -> ParserResult<CommandData> {
+        alt((
+            map(
+                preceded(take(24u32), Self::parse_parameter_data),
+                CommandData::Parameters,
+            ),
+            Self::parse_unknown,
+        ))(input)
+    }
+
+    // length_data asks for more input when the length runs past the end of the command, which
+    // complete turns into an error so that callers fall back to unknown instead of failing the
+    // whole replay
+    fn parse_parameter_data(input: Span) -> ParserResult<Vec<u8>> {
+        alt((
+            map(verify(le_u8, |flags| *flags == 0xff), |_| Vec::new()),
+            map(
+                preceded(le_u8, complete(length_data(le_u8))),
+                |data: Span| data.fragment().to_vec(),
+            ),
+        ))(input)
+    }
+
     pub fn parse_unknown(input: Span) -> ParserResult<CommandData> {
         map(rest, |_| CommandData::Unknown)(input)
     }
@@ -61,7 +287,9 @@ impl CommandData {
         command_type: CommandType,
     ) -> impl FnMut(Span) -> ParserResult<CommandData> {
         match command_type {
-            CommandType::PCMD_AIPlayer => Self::parse_empty,
+            CommandType::PCMD_AIPlayer
+            | CommandType::PCMD_Surrender
+            | CommandType::PCMD_TentativeUpgradeRemoveAll => Self::parse_empty,
             CommandType::PCMD_Ability
             | CommandType::PCMD_InstantUpgrade
             | CommandType::PCMD_TentativeUpgrade => Self::parse_pbgid,
@@ -70,6 +298,32 @@ impl CommandData {
             }
             CommandType::CMD_CancelConstruction => Self::parse_sourced,
             CommandType::CMD_CancelProduction => Self::parse_sourced_index,
+            CommandType::SCMD_CancelProduction => Self::parse_selected_index,
+            CommandType::CMD_Move
+            | CommandType::SCMD_Move
+            | CommandType::SCMD_AttackMove
+            | CommandType::SCMD_Retreat
+            | CommandType::SCMD_Stop
+            | CommandType::FCMD_FormationSquadGroupMove
+            | CommandType::CMD_BuildStructure
+            | CommandType::SCMD_BuildStructure => Self::parse_targeted,
+            CommandType::SCMD_Face
+            | CommandType::SCMD_CaptureTeamWeapon
+            | CommandType::SCMD_Recrew
+            | CommandType::SCMD_Load
+            | CommandType::SCMD_Unload
+            | CommandType::CMD_UnloadSquads
+            | CommandType::SCMD_UnloadSquads
+            | CommandType::CMD_Capture
+            | CommandType::SCMD_Capture => Self::parse_unqueued_targeted,
+            CommandType::SCMD_Ability
+            | CommandType::SCMD_Upgrade
+            | CommandType::SCMD_ReinforceUnit => Self::parse_targeted_pbgid,
+            CommandType::PCMD_PlaceAndConstructEntities => Self::parse_placement,
+            CommandType::PCMD_CheatResources
+            | CommandType::PCMD_CheatRevealAll
+            | CommandType::PCMD_CheatBuildTime
+            | CommandType::PCMD_CheatIgnoreCosts => Self::parse_parameters,
             _ => Self::parse_unknown,
         }
     }
@@ -81,23 +335,34 @@ pub struct Command {
     pub player_id: u8,
     pub index: u32,
     pub data: CommandData,
+    pub offset: usize,
     #[cfg(feature = "raw")]
     pub bytes: Vec<u8>,
 }
 
 impl Command {
     pub fn parse(input: Span) -> ParserResult<Command> {
+        let offset = input.location_offset();
+
         map(
             length_value(
                 peek(le_u16),
-                tuple((le_u16, flat_map(CommandType::parse, Self::parse_type))),
+                tuple((
+                    le_u16,
+                    flat_map(CommandType::parse, move |action_type| {
+                        Self::parse_type(action_type, offset)
+                    }),
+                )),
             ),
             |(_length, command)| command,
         )(input)
     }
 
     #[cfg(not(feature = "raw"))]
-    fn parse_type(action_type: CommandType) -> impl FnMut(Span) -> ParserResult<Command> {
+    fn parse_type(
+        action_type: CommandType,
+        offset: usize,
+    ) -> impl FnMut(Span) -> ParserResult<Command> {
         move |input: Span| {
             map(
                 tuple((le_u8, le_u32, CommandData::parser_for_type(action_type))),
@@ -106,13 +371,17 @@ impl Command {
                     player_id: player_id & 0b0111_1111, // bit mask to turn eg 0x87 into 0x7
                     index,
                     data,
+                    offset,
                 },
             )(input)
         }
     }
 
     #[cfg(feature = "raw")]
-    fn parse_type(action_type: CommandType) -> impl FnMut(Span) -> ParserResult<Command> {
+    fn parse_type(
+        action_type: CommandType,
+        offset: usize,
+    ) -> impl FnMut(Span) -> ParserResult<Command> {
         use nom::{combinator::eof, multi::many_till};
 
         move |input: Span| {
@@ -128,6 +397,7 @@ impl Command {
                     player_id: player_id & 0b0111_1111, // bit mask to turn eg 0x87 into 0x7,
                     index,
                     data,
+                    offset,
                     bytes,
                 },
             )(input)
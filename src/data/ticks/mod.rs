@@ -8,6 +8,8 @@ mod tick;
 pub use crate::data::ticks::bundle::Bundle;
 pub use crate::data::ticks::command::Command;
 pub use crate::data::ticks::command::CommandData;
+pub use crate::data::ticks::command::Target;
+pub use crate::data::ticks::command::Vector;
 pub use crate::data::ticks::command_tick::CommandTick;
 pub use crate::data::ticks::message::Message;
 pub use crate::data::ticks::message_tick::MessageTick;
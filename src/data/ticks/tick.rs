@@ -1,6 +1,10 @@
 use crate::data::ticks::{CommandTick, MessageTick};
 use crate::data::{ParserResult, Span};
 use nom::branch::alt;
+use nom::combinator::{complete, map};
+use nom::multi::length_data;
+use nom::number::complete::le_u32;
+use nom::sequence::tuple;
 
 #[derive(Debug)]
 pub enum Tick {
@@ -10,6 +14,14 @@ pub enum Tick {
 
 impl Tick {
     pub fn parse(input: Span) -> ParserResult<Tick> {
-        alt((CommandTick::parse_tick, MessageTick::parse_tick))(input)
+        complete(alt((CommandTick::parse_tick, MessageTick::parse_tick)))(input)
+    }
+
+    // steps over a tick using only its length prefix, returning whether it was a command tick
+    pub fn skip(input: Span) -> ParserResult<bool> {
+        complete(map(
+            tuple((le_u32, length_data(le_u32))),
+            |(tick_type, _)| tick_type == 0,
+        ))(input)
     }
 }
@@ -1,9 +1,185 @@
 //! Error representations, primarily related to parsing failure.
 
-use crate::data::Span;
+use crate::command_type::CommandType;
+use crate::data::{ParseError, ParseErrorKind};
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::io;
 
-/// Represents a failure in parsing at some point in the combinator chain. Parsing fails eagerly;
-/// that is, the first failure point hit will exit with an error. This type returns an error that
-/// includes information on the segment of bytes being parsed and the location of the cursor at
-/// time of failure.
-pub type ParseError<'a> = nom::Err<nom::error::Error<Span<'a>>>;
+/// Represents a failure at some point while reading a replay, parsing it, or converting the parsed
+/// data into its public representation. Parsing fails eagerly; that is, the first failure point hit
+/// will exit with an error.
+///
+/// Every variant other than `Io` carries the byte offset into the replay file at which the failure
+/// was detected, alongside the path of the chunk being parsed at the time (for example
+/// `FOLDINFO/DATADATA`).
+/// Segments of the file that aren't chunks are given the names `HEADER`, `CHUNKY`, and `TICKS`. The
+/// error owns all of its data, so it can outlive the buffer the replay was parsed from.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum VaultError {
+    /// The replay could not be read from its source. `message` is the description of the
+    /// underlying I/O error.
+    Io {
+        kind: io::ErrorKind,
+        message: String,
+    },
+    /// The input ended before a complete structure could be read, either at the end of the file or
+    /// at the end of a length-prefixed section.
+    Truncated { offset: usize, path: String },
+    /// A chunk or chunky header did not begin with a recognized signature. `signature` contains a
+    /// lossy UTF-8 representation of the bytes found in its place.
+    InvalidChunkSignature {
+        offset: usize,
+        path: String,
+        signature: String,
+    },
+    /// A chunk required to build a `Replay` was not present in the file. The offset points to the
+    /// end of the chunk section of the file, which is the latest point at which it could have been
+    /// found.
+    MissingChunk { offset: usize, path: String },
+    /// A player was assigned a faction that isn't recognized.
+    UnknownFaction {
+        offset: usize,
+        path: String,
+        faction: String,
+    },
+    /// A player was assigned a team that isn't recognized.
+    UnknownTeam {
+        offset: usize,
+        path: String,
+        team: u32,
+    },
+    /// A human player's Steam ID could not be parsed as an integer.
+    InvalidSteamId {
+        offset: usize,
+        path: String,
+        steam_id: String,
+    },
+    /// A command's data did not match the layout expected for its command type.
+    CommandMismatch {
+        offset: usize,
+        path: String,
+        command_type: CommandType,
+    },
+    /// Any other failure to parse the data found at the given location. `reason` is a short
+    /// description of the check that failed.
+    Malformed {
+        offset: usize,
+        path: String,
+        reason: String,
+    },
+}
+
+impl VaultError {
+    /// The byte offset into the replay file at which the failure was detected. Always 0 for
+    /// `VaultError::Io`, since no data was parsed.
+    pub fn offset(&self) -> usize {
+        match self {
+            VaultError::Io { .. } => 0,
+            VaultError::Truncated { offset, .. }
+            | VaultError::InvalidChunkSignature { offset, .. }
+            | VaultError::MissingChunk { offset, .. }
+            | VaultError::UnknownFaction { offset, .. }
+            | VaultError::UnknownTeam { offset, .. }
+            | VaultError::InvalidSteamId { offset, .. }
+            | VaultError::CommandMismatch { offset, .. }
+            | VaultError::Malformed { offset, .. } => *offset,
+        }
+    }
+
+    /// The path of the chunk being parsed when the failure was detected, with nested chunks
+    /// separated by slashes. Empty if the failure happened outside of any known segment, and
+    /// always empty for `VaultError::Io`.
+    pub fn path(&self) -> &str {
+        match self {
+            VaultError::Io { .. } => "",
+            VaultError::Truncated { path, .. }
+            | VaultError::InvalidChunkSignature { path, .. }
+            | VaultError::MissingChunk { path, .. }
+            | VaultError::UnknownFaction { path, .. }
+            | VaultError::UnknownTeam { path, .. }
+            | VaultError::InvalidSteamId { path, .. }
+            | VaultError::CommandMismatch { path, .. }
+            | VaultError::Malformed { path, .. } => path,
+        }
+    }
+
+    pub(crate) fn from_nom(err: nom::Err<ParseError>, input_len: usize) -> Self {
+        match err {
+            nom::Err::Error(err) | nom::Err::Failure(err) => err.into(),
+            nom::Err::Incomplete(_) => VaultError::Truncated {
+                offset: input_len,
+                path: String::new(),
+            },
+        }
+    }
+}
+
+impl Display for VaultError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            VaultError::Io { message, .. } => {
+                return write!(f, "failed to read replay: {}", message)
+            }
+            VaultError::Truncated { .. } => write!(f, "unexpected end of input")?,
+            VaultError::InvalidChunkSignature { signature, .. } => {
+                write!(f, "invalid chunk signature {:?}", signature)?
+            }
+            VaultError::MissingChunk { .. } => write!(f, "missing required chunk")?,
+            VaultError::UnknownFaction { faction, .. } => {
+                write!(f, "unknown faction {:?}", faction)?
+            }
+            VaultError::UnknownTeam { team, .. } => write!(f, "unknown team ID {}", team)?,
+            VaultError::InvalidSteamId { steam_id, .. } => {
+                write!(f, "invalid Steam ID {:?}", steam_id)?
+            }
+            VaultError::CommandMismatch { command_type, .. } => {
+                write!(f, "unexpected data for command type {:?}", command_type)?
+            }
+            VaultError::Malformed { reason, .. } => write!(f, "malformed data: {}", reason)?,
+        }
+
+        if self.path().is_empty() {
+            write!(f, " at offset {}", self.offset())
+        } else {
+            write!(f, " at offset {} ({})", self.offset(), self.path())
+        }
+    }
+}
+
+impl Error for VaultError {}
+
+impl From<io::Error> for VaultError {
+    fn from(err: io::Error) -> Self {
+        VaultError::Io {
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+}
+
+impl From<ParseError> for VaultError {
+    fn from(err: ParseError) -> Self {
+        let offset = err.offset;
+        let path = err.path.clone();
+
+        if err.is_truncation() {
+            return VaultError::Truncated { offset, path };
+        }
+
+        match err.kind {
+            ParseErrorKind::ChunkSignature(signature) => VaultError::InvalidChunkSignature {
+                offset,
+                path,
+                signature,
+            },
+            ParseErrorKind::Nom(kind) => VaultError::Malformed {
+                offset,
+                path,
+                reason: kind.description().to_lowercase(),
+            },
+        }
+    }
+}
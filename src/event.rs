@@ -0,0 +1,263 @@
+//! Representation of the chronological stream of commands and messages in a replay.
+
+use crate::command::{Command, CommandCategory};
+use crate::data::ticks::Tick;
+use crate::data::Replay as ReplayData;
+use crate::game_time::GameTime;
+use crate::message::Message;
+use crate::player::Player;
+use std::slice;
+use std::vec;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A single command or chat message from any player, as yielded by `Replay::events`. Unlike
+/// `Player::commands` and `Player::messages`, events from every player are interleaved in the
+/// order they appear in the replay file, so commands issued on the same tick keep the order the
+/// game executed them in.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Event"))]
+pub struct Event {
+    tick: GameTime,
+    player_id: u32,
+    kind: EventKind,
+}
+
+impl Event {
+    /// The tick at which the command was executed or the message was sent.
+    pub fn tick(&self) -> GameTime {
+        self.tick
+    }
+    /// The ID of the player who issued the command or sent the message. See `Player::id`.
+    pub fn player_id(&self) -> u32 {
+        self.player_id
+    }
+    /// What happened. See `EventKind` for details.
+    pub fn kind(&self) -> &EventKind {
+        &self.kind
+    }
+    /// The command this event represents, whether or not the player triggered it, or `None` if
+    /// the event is a message.
+    pub fn command(&self) -> Option<&Command> {
+        match &self.kind {
+            EventKind::Command(command) | EventKind::SystemCommand(command) => Some(command),
+            EventKind::Message(_) => None,
+        }
+    }
+    /// The message this event represents, or `None` if the event is a command.
+    pub fn message(&self) -> Option<&Message> {
+        match &self.kind {
+            EventKind::Message(message) => Some(message),
+            _ => None,
+        }
+    }
+}
+
+// this is safe as Event does not contain any Ruby types
+#[cfg(feature = "magnus")]
+unsafe impl magnus::IntoValueFromNative for Event {}
+
+/// The kinds of event found in a replay.
+
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::EventKind"))]
+pub enum EventKind {
+    /// A command the player issued themselves.
+    Command(Command),
+    /// A chat message the player sent.
+    Message(Message),
+    /// A command the game issued on the player's behalf rather than one triggered by player
+    /// action, which is any command with an index of 0. See `Command::index`.
+    SystemCommand(Command),
+}
+
+// where each event in the replay came from, in file order. only the player is recorded, since a
+// player's commands and messages are already stored in file order, so the events themselves can be
+// rebuilt by taking the next command or message of that player
+#[derive(Debug, Copy, Clone)]
+pub(crate) enum EventSource {
+    Command(usize),
+    Message(usize),
+}
+
+pub(crate) fn event_sources_from_data(data: &ReplayData, players: &[Player]) -> Vec<EventSource> {
+    let player_index = |id: u32| players.iter().position(|player| player.id() == id);
+    let name_index = |name: &str| players.iter().position(|player| player.name() == name);
+
+    data.ticks
+        .iter()
+        .flat_map(|tick| match tick {
+            Tick::Command(tick) => tick
+                .bundles
+                .iter()
+                .flat_map(|bundle| bundle.commands.iter())
+                .filter_map(|command| player_index(command.player_id as u32))
+                .map(EventSource::Command)
+                .collect::<Vec<_>>(),
+            Tick::Message(tick) => tick
+                .messages
+                .iter()
+                .filter_map(|message| name_index(&message.name))
+                .map(EventSource::Message)
+                .collect(),
+        })
+        .collect()
+}
+
+/// An iterator over the events in a replay, in the order they appear in the replay file. Filters
+/// can be chained to only yield some events:
+///
+/// ```ignore
+/// let retreats = replay
+///     .events()
+///     .between(GameTime::from_secs(300), GameTime::from_secs(600))
+///     .player(player_id)
+///     .category(CommandCategory::Movement);
+/// ```
+///
+/// To access, see `Replay::events`.
+pub struct Events<'a> {
+    sources: slice::Iter<'a, EventSource>,
+    players: Vec<(u32, vec::IntoIter<Command>, vec::IntoIter<Message>)>,
+    filter: EventFilter,
+}
+
+#[derive(Default)]
+struct EventFilter {
+    start: Option<GameTime>,
+    end: Option<GameTime>,
+    player_id: Option<u32>,
+    category: Option<CommandCategory>,
+}
+
+impl EventFilter {
+    fn matches(&self, event: &Event) -> bool {
+        self.start.into_iter().all(|start| event.tick >= start)
+            && self.end.into_iter().all(|end| event.tick < end)
+            && self.player_id.into_iter().all(|id| event.player_id == id)
+            && self.category.into_iter().all(|category| {
+                event
+                    .command()
+                    .into_iter()
+                    .any(|command| command.category() == category)
+            })
+    }
+}
+
+impl<'a> Events<'a> {
+    pub(crate) fn new(sources: &'a [EventSource], players: &[Player]) -> Self {
+        Self {
+            sources: sources.iter(),
+            players: players
+                .iter()
+                .map(|player| {
+                    (
+                        player.id(),
+                        player.commands().into_iter(),
+                        player.messages().into_iter(),
+                    )
+                })
+                .collect(),
+            filter: EventFilter::default(),
+        }
+    }
+
+    /// Only yields events from `start` up to but not including `end`.
+    pub fn between(self, start: GameTime, end: GameTime) -> Self {
+        Self {
+            filter: EventFilter {
+                start: Some(start),
+                end: Some(end),
+                ..self.filter
+            },
+            ..self
+        }
+    }
+
+    /// Only yields events from `start` onwards.
+    pub fn since(self, start: GameTime) -> Self {
+        Self {
+            filter: EventFilter {
+                start: Some(start),
+                ..self.filter
+            },
+            ..self
+        }
+    }
+
+    /// Only yields events from before `end`.
+    pub fn until(self, end: GameTime) -> Self {
+        Self {
+            filter: EventFilter {
+                end: Some(end),
+                ..self.filter
+            },
+            ..self
+        }
+    }
+
+    /// Only yields events from the player with the given ID. See `Player::id`.
+    pub fn player(self, player_id: u32) -> Self {
+        Self {
+            filter: EventFilter {
+                player_id: Some(player_id),
+                ..self.filter
+            },
+            ..self
+        }
+    }
+
+    /// Only yields commands of the given category, leaving out messages. See
+    /// `Command::category`.
+    pub fn category(self, category: CommandCategory) -> Self {
+        Self {
+            filter: EventFilter {
+                category: Some(category),
+                ..self.filter
+            },
+            ..self
+        }
+    }
+}
+
+impl<'a> Iterator for Events<'a> {
+    type Item = Event;
+
+    fn next(&mut self) -> Option<Event> {
+        // every source has to be consumed to keep each player's position, even when filtered out
+        for source in self.sources.by_ref() {
+            let event = match *source {
+                EventSource::Command(index) => {
+                    let (player_id, commands, _) = &mut self.players[index];
+                    commands.next().map(|command| Event {
+                        tick: command.tick(),
+                        player_id: *player_id,
+                        kind: if command.index() == 0 {
+                            EventKind::SystemCommand(command)
+                        } else {
+                            EventKind::Command(command)
+                        },
+                    })
+                }
+                EventSource::Message(index) => {
+                    let (player_id, _, messages) = &mut self.players[index];
+                    messages.next().map(|message| Event {
+                        tick: message.tick(),
+                        player_id: *player_id,
+                        kind: EventKind::Message(message),
+                    })
+                }
+            };
+
+            match event {
+                Some(event) if self.filter.matches(&event) => return Some(event),
+                _ => continue,
+            }
+        }
+        None
+    }
+}
@@ -0,0 +1,131 @@
+//! Representation of time within a match.
+
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::ops::{Add, AddAssign, Sub, SubAssign};
+use std::time::Duration;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The number of ticks CoH3's engine executes per second of game time.
+pub const TICKS_PER_SECOND: u32 = 8;
+
+/// A point in time within a match, or the length of time between two such points, measured in
+/// ticks of the game engine. Ticks are the unit replays record time in; see `TICKS_PER_SECOND`
+/// for how they relate to real time.
+///
+/// Game times display as minutes and seconds (`mm:ss`), the same way the in-game clock does, and
+/// can be compared, added and subtracted. With the `serde` feature enabled, they serialize as both
+/// the number of ticks and the equivalent number of seconds:
+///
+/// ```ignore
+/// { "ticks": 100, "seconds": 12.5 }
+/// ```
+
+#[derive(Debug, Default, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(
+    feature = "serde",
+    derive(Serialize, Deserialize),
+    serde(into = "GameTimeRepr", from = "GameTimeRepr")
+)]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::GameTime"))]
+pub struct GameTime(u32);
+
+impl GameTime {
+    /// A game time of the given number of ticks.
+    pub const fn from_ticks(ticks: u32) -> Self {
+        Self(ticks)
+    }
+    /// A game time of the given number of whole seconds.
+    pub const fn from_secs(secs: u32) -> Self {
+        Self(secs * TICKS_PER_SECOND)
+    }
+    /// The number of ticks this game time represents.
+    pub fn ticks(&self) -> u32 {
+        self.0
+    }
+    /// The number of seconds this game time represents, including fractions of a second.
+    pub fn as_secs_f64(&self) -> f64 {
+        self.0 as f64 / TICKS_PER_SECOND as f64
+    }
+    /// The real time this game time represents, assuming the match was played at normal speed.
+    pub fn as_duration(&self) -> Duration {
+        Duration::from_millis(self.0 as u64 * 1000 / TICKS_PER_SECOND as u64)
+    }
+    /// Subtracts another game time from this one, returning `None` if the result would be
+    /// negative.
+    pub fn checked_sub(self, other: GameTime) -> Option<GameTime> {
+        self.0.checked_sub(other.0).map(Self)
+    }
+}
+
+impl Display for GameTime {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let secs = self.0 / TICKS_PER_SECOND;
+        write!(f, "{:02}:{:02}", secs / 60, secs % 60)
+    }
+}
+
+impl From<GameTime> for Duration {
+    fn from(time: GameTime) -> Self {
+        time.as_duration()
+    }
+}
+
+impl Add for GameTime {
+    type Output = GameTime;
+
+    fn add(self, other: GameTime) -> GameTime {
+        Self(self.0 + other.0)
+    }
+}
+
+impl AddAssign for GameTime {
+    fn add_assign(&mut self, other: GameTime) {
+        self.0 += other.0;
+    }
+}
+
+impl Sub for GameTime {
+    type Output = GameTime;
+
+    /// Panics if `other` is later than `self`; see `GameTime::checked_sub` to avoid this.
+    fn sub(self, other: GameTime) -> GameTime {
+        self.checked_sub(other)
+            .expect("overflow when subtracting game times")
+    }
+}
+
+impl SubAssign for GameTime {
+    fn sub_assign(&mut self, other: GameTime) {
+        *self = *self - other;
+    }
+}
+
+// the serialized form of a game time. seconds are only written for convenience, so they're
+// optional when deserializing and ticks take precedence
+#[cfg(feature = "serde")]
+#[derive(Serialize, Deserialize)]
+struct GameTimeRepr {
+    ticks: u32,
+    #[serde(default)]
+    seconds: f64,
+}
+
+#[cfg(feature = "serde")]
+impl From<GameTime> for GameTimeRepr {
+    fn from(time: GameTime) -> Self {
+        Self {
+            ticks: time.ticks(),
+            seconds: time.as_secs_f64(),
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+impl From<GameTimeRepr> for GameTime {
+    fn from(repr: GameTimeRepr) -> Self {
+        Self::from_ticks(repr.ticks)
+    }
+}
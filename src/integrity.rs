@@ -0,0 +1,176 @@
+//! Representation of replay integrity checks.
+
+use crate::command::Command;
+use crate::command_data::{Cheat, CheatKind};
+use crate::game_time::GameTime;
+use crate::replay::Replay;
+use uuid::Uuid;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// The result of checking a replay for signs that the match wasn't played under normal
+/// conditions: developer cheats, mods that weren't approved, and inconsistencies in the replay's
+/// own data that could point to a modified file. To create, see `Replay::integrity_report`.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::IntegrityReport"))]
+pub struct IntegrityReport {
+    cheats: Vec<CheatUse>,
+    unapproved_mod: Option<Uuid>,
+    anomalies: Vec<Anomaly>,
+}
+
+impl IntegrityReport {
+    /// Every cheat used in the match, sorted by player and then chronologically.
+    pub fn cheats(&self) -> Vec<CheatUse> {
+        self.cheats.clone()
+    }
+    /// The UUID of the mod the match was played with if it wasn't in the list of approved mods,
+    /// or `None` if the match was unmodded or used an approved mod.
+    pub fn unapproved_mod(&self) -> Option<Uuid> {
+        self.unapproved_mod
+    }
+    /// Inconsistencies found in the replay's data. See `Anomaly` for the checks performed.
+    pub fn anomalies(&self) -> Vec<Anomaly> {
+        self.anomalies.clone()
+    }
+    /// Whether any of the checks failed, meaning the replay shouldn't be trusted to represent a
+    /// normal match.
+    pub fn is_tainted(&self) -> bool {
+        !self.cheats.is_empty() || self.unapproved_mod.is_some() || !self.anomalies.is_empty()
+    }
+}
+
+/// A cheat used by a player. Cheats are flagged by their command type, so a cheat is reported even
+/// if its parameters couldn't be decoded.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::CheatUse"))]
+pub struct CheatUse {
+    player_id: u32,
+    tick: GameTime,
+    kind: CheatKind,
+    cheat: Option<Cheat>,
+}
+
+impl CheatUse {
+    /// The ID of the player who used the cheat. See `Player::id`.
+    pub fn player_id(&self) -> u32 {
+        self.player_id
+    }
+    /// The tick at which the cheat was used.
+    pub fn tick(&self) -> GameTime {
+        self.tick
+    }
+    /// Which cheat was used.
+    pub fn kind(&self) -> CheatKind {
+        self.kind
+    }
+    /// The decoded cheat command, or `None` if the command's parameters didn't match the expected
+    /// layout and it was parsed as `Command::Unknown` instead.
+    pub fn cheat(&self) -> Option<Cheat> {
+        self.cheat.clone()
+    }
+}
+
+// this is safe as CheatUse does not contain any Ruby types
+#[cfg(feature = "magnus")]
+unsafe impl magnus::IntoValueFromNative for CheatUse {}
+
+/// An inconsistency in a replay's data. None of these occur in replays written by the game, so
+/// they suggest the file was modified after it was recorded.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Anomaly"))]
+pub enum Anomaly {
+    /// More than one player has the given ID.
+    DuplicatePlayerId(u32),
+    /// The player with the given ID isn't in the list of players connected to the match. See
+    /// `Replay::player_ids`.
+    UnlistedPlayer(u32),
+    /// The given ID is in the list of players connected to the match, but no such player exists.
+    MissingPlayer(u32),
+    /// The replay says it was recorded by a player with the given ID, but no such player exists.
+    UnknownRecorder(u32),
+}
+
+// this is safe as Anomaly does not contain any Ruby types
+#[cfg(feature = "magnus")]
+unsafe impl magnus::IntoValueFromNative for Anomaly {}
+
+pub(crate) fn integrity_report_from_replay(
+    replay: &Replay,
+    approved_mods: &[Uuid],
+) -> IntegrityReport {
+    let players = replay.players();
+    let mod_uuid = replay.mod_uuid();
+
+    let cheats = players
+        .iter()
+        .flat_map(|player| {
+            player
+                .commands()
+                .into_iter()
+                .filter_map(move |command| match command {
+                    Command::Cheat(cheat) => Some(CheatUse {
+                        player_id: player.id(),
+                        tick: cheat.tick(),
+                        kind: cheat.kind(),
+                        cheat: Some(cheat),
+                    }),
+                    Command::Unknown(data) => {
+                        CheatKind::from_command_type(data.action_type()).map(|kind| CheatUse {
+                            player_id: player.id(),
+                            tick: data.tick(),
+                            kind,
+                            cheat: None,
+                        })
+                    }
+                    _ => None,
+                })
+        })
+        .collect();
+
+    let unapproved_mod = if mod_uuid.is_nil() || approved_mods.contains(&mod_uuid) {
+        None
+    } else {
+        Some(mod_uuid)
+    };
+
+    let mut anomalies = Vec::new();
+    let ids: Vec<_> = players.iter().map(|player| player.id()).collect();
+    for (idx, id) in ids.iter().enumerate() {
+        if ids[..idx].contains(id) && !ids[idx + 1..].contains(id) {
+            anomalies.push(Anomaly::DuplicatePlayerId(*id));
+        }
+    }
+    // the player list chunk isn't present in every replay, in which case there's nothing to check
+    let listed = replay.player_ids();
+    if !listed.is_empty() {
+        for id in &ids {
+            if !listed.contains(id) {
+                anomalies.push(Anomaly::UnlistedPlayer(*id));
+            }
+        }
+        for id in &listed {
+            if !ids.contains(id) {
+                anomalies.push(Anomaly::MissingPlayer(*id));
+            }
+        }
+    }
+    if let Some(id) = replay.recorder_id() {
+        if !ids.contains(&id) {
+            anomalies.push(Anomaly::UnknownRecorder(id));
+        }
+    }
+
+    IntegrityReport {
+        cheats,
+        unapproved_mod,
+        anomalies,
+    }
+}
@@ -15,25 +15,61 @@
 //! }
 //! ```
 
+pub mod analytics;
+pub mod attributes;
+mod capture;
 mod command;
 pub mod command_data;
 mod command_type;
 mod data;
 mod errors;
+mod event;
+mod game_time;
+mod integrity;
+mod loadout;
+pub mod locale;
 mod map;
 mod message;
+mod outcome;
 mod player;
+mod production;
 mod replay;
+mod settings;
+mod summary;
 
+pub use crate::capture::CaptureOrder;
+pub use crate::capture::StrategicPoint;
 pub use crate::command::Command;
+pub use crate::command::CommandCategory;
 #[cfg(feature = "raw")]
 pub use crate::command::RawCommand;
 pub use crate::command_type::CommandType;
-pub use crate::errors::ParseError;
+pub use crate::errors::VaultError;
+pub use crate::event::Event;
+pub use crate::event::EventKind;
+pub use crate::event::Events;
+pub use crate::game_time::GameTime;
+pub use crate::game_time::TICKS_PER_SECOND;
+pub use crate::integrity::Anomaly;
+pub use crate::integrity::CheatUse;
+pub use crate::integrity::IntegrityReport;
+pub use crate::loadout::LoadoutCatalog;
+pub use crate::loadout::LoadoutCategory;
+pub use crate::loadout::LoadoutItem;
 pub use crate::map::Map;
 pub use crate::message::Message;
+pub use crate::outcome::Confidence;
+pub use crate::outcome::EndReason;
+pub use crate::outcome::MatchResult;
+pub use crate::outcome::Outcome;
+pub use crate::outcome::PlayerResult;
+pub use crate::player::AiProfile;
 pub use crate::player::Faction;
 pub use crate::player::Player;
 pub use crate::player::Team;
+pub use crate::production::ProductionItem;
 pub use crate::replay::GameType;
 pub use crate::replay::Replay;
+pub use crate::settings::MatchSettings;
+pub use crate::settings::WinCondition;
+pub use crate::summary::ReplaySummary;
@@ -0,0 +1,164 @@
+//! Representation of parsed player loadout information.
+
+use crate::data::Item;
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// An item a player brought into the match, such as a battlegroup or a cosmetic. Loadouts are
+/// recorded alongside the rest of the player information at the start of the replay, so they are
+/// available even when ticks aren't parsed. To access, see `Player::loadout`.
+///
+/// Items belonging to human players are instances of items in the player's Relic inventory and
+/// include details about that instance, while items belonging to AI players only carry the pbgid
+/// of the item.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::LoadoutItem"))]
+pub struct LoadoutItem {
+    category: LoadoutCategory,
+    id: u64,
+    instance_id: Option<u64>,
+    profile_id: Option<u64>,
+    durability: Option<u32>,
+    durability_type: Option<u32>,
+    metadata: Option<String>,
+    timestamp: Option<u64>,
+    location_id: Option<u64>,
+}
+
+impl LoadoutItem {
+    /// The loadout list this item was recorded in, which is either `LoadoutCategory::Battlegroup`
+    /// or `LoadoutCategory::Cosmetic`. Use `LoadoutItem::category_in` to tell cosmetics apart.
+    pub fn category(&self) -> LoadoutCategory {
+        self.category
+    }
+    /// The category the given catalog lists this item's ID under, falling back to
+    /// `LoadoutItem::category` if the catalog doesn't have it.
+    pub fn category_in(&self, catalog: &LoadoutCatalog) -> LoadoutCategory {
+        catalog.get(self.id).unwrap_or(self.category)
+    }
+    /// For human players, this is the ID of the item definition on Relic's servers, which is the
+    /// same for every player who owns the item. For AI players, this is the pbgid of the item,
+    /// which is a unique identifier for the item in the game's attribute data. In both cases the
+    /// ID can be used to look up what the item is, and so whether a cosmetic is a faction
+    /// cosmetic, unit skin, emote, etc.
+    pub fn id(&self) -> u64 {
+        self.id
+    }
+    /// The ID of this specific instance of the item in the player's inventory, or `None` if the
+    /// player is AI.
+    pub fn instance_id(&self) -> Option<u64> {
+        self.instance_id
+    }
+    /// The Relic profile ID of the player that owns the item, or `None` if the player is AI. This
+    /// should always match `Player::profile_id`.
+    pub fn profile_id(&self) -> Option<u64> {
+        self.profile_id
+    }
+    /// The remaining durability of the item, or `None` if the player is AI. For most items this is
+    /// 1; items that can be owned in multiple copies record the number owned.
+    pub fn durability(&self) -> Option<u32> {
+        self.durability
+    }
+    /// The durability type of the item, or `None` if the player is AI.
+    pub fn durability_type(&self) -> Option<u32> {
+        self.durability_type
+    }
+    /// JSON-encoded metadata Relic attaches to the item instance, or `None` if the player is AI.
+    /// Includes information such as the slot the item is equipped in (`eslot`/`epos`).
+    pub fn metadata(&self) -> Option<&str> {
+        self.metadata.as_deref()
+    }
+    /// Unix timestamp (in seconds) at which the player acquired the item, or `None` if the player
+    /// is AI.
+    pub fn timestamp(&self) -> Option<u64> {
+        self.timestamp
+    }
+    /// The ID of the inventory location the item is stored in, or `None` if the player is AI.
+    pub fn location_id(&self) -> Option<u64> {
+        self.location_id
+    }
+}
+
+pub(crate) fn loadout_item_from_data(item: &Item, category: LoadoutCategory) -> LoadoutItem {
+    let details = item.details.as_ref();
+
+    LoadoutItem {
+        category,
+        id: item.id,
+        instance_id: details.map(|details| details.instance_id),
+        profile_id: details.map(|details| details.profile_id),
+        durability: details.map(|details| details.durability),
+        durability_type: details.map(|details| details.durability_type),
+        metadata: details.map(|details| details.metadata.clone()),
+        timestamp: details.map(|details| details.timestamp),
+        location_id: details.map(|details| details.location_id),
+    }
+}
+
+// this is safe as LoadoutItem does not contain any Ruby types
+#[cfg(feature = "magnus")]
+unsafe impl magnus::IntoValueFromNative for LoadoutItem {}
+
+/// The kind of a loadout item. Replays store battlegroups and cosmetics in separate lists, so
+/// `LoadoutItem::category` is always `Battlegroup` or `Cosmetic`; finer distinctions between
+/// cosmetics aren't recorded in the replay and have to be looked up by item ID with a
+/// `LoadoutCatalog`. More kinds of item may be added in future versions.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::LoadoutCategory"))]
+#[non_exhaustive]
+pub enum LoadoutCategory {
+    /// A battlegroup the player can pick during the match.
+    Battlegroup,
+    /// A cosmetic whose kind isn't known.
+    Cosmetic,
+    /// A cosmetic applied to the player's whole faction.
+    FactionCosmetic,
+    /// A skin for a single unit type.
+    UnitSkin,
+    /// An emote.
+    Emote,
+}
+
+/// A lookup table of loadout item categories by item ID, used to tell cosmetics apart with
+/// `LoadoutItem::category_in`. Replays don't say what kind of cosmetic an item is, and `vault`
+/// doesn't ship item definitions, so the table has to be built from item data supplied by the
+/// caller. Since human and AI items use different kinds of ID (see `LoadoutItem::id`), a catalog
+/// that covers both should include both Relic item definition IDs and pbgids.
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LoadoutCatalog {
+    categories: HashMap<u64, LoadoutCategory>,
+}
+
+impl LoadoutCatalog {
+    /// Collects the given pairs of item ID and category. If an ID is given more than once, the
+    /// last category is kept.
+    pub fn new(categories: impl IntoIterator<Item = (u64, LoadoutCategory)>) -> Self {
+        Self {
+            categories: categories.into_iter().collect(),
+        }
+    }
+
+    /// Sets the category of the item with the given ID, replacing any existing category.
+    pub fn insert(&mut self, id: u64, category: LoadoutCategory) {
+        self.categories.insert(id, category);
+    }
+    /// The category of the item with the given ID, or `None` if the catalog doesn't have it.
+    pub fn get(&self, id: u64) -> Option<LoadoutCategory> {
+        self.categories.get(&id).copied()
+    }
+    /// The number of items in the catalog.
+    pub fn len(&self) -> usize {
+        self.categories.len()
+    }
+    /// Whether the catalog has no items.
+    pub fn is_empty(&self) -> bool {
+        self.categories.is_empty()
+    }
+}
@@ -0,0 +1,205 @@
+//! Lookup of localized strings from CoH3 `.ucs` localization files.
+//!
+//! Names and descriptions in replays and attribute data are given as localization IDs such as
+//! `$11233954`, which refer to strings in the game's localization files. Each language has its own
+//! `.ucs` file (for example `english.ucs`), which is UTF-16 text with one string per line, given
+//! as the numeric ID and the string separated by a tab (shown here as `\t`):
+//!
+//! ```ignore
+//! 11233954\tDesert Airfield
+//! 11233955\tA hard-fought airfield in the North African desert.
+//! ```
+//!
+//! Load one `Locale` per language, and collect them into `Locales` to resolve names in several
+//! languages side by side:
+//!
+//! ```ignore
+//! fn main() {
+//!     let locale = vault::locale::Locale::from_ucs_path("english", "/path/to/english.ucs").unwrap();
+//!     let replay = vault::Replay::from_path("/path/to/replay.rec").unwrap();
+//!     println!("{:?}", replay.map().localized_name(&locale));
+//! }
+//! ```
+
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+/// The strings of a single language, parsed from a `.ucs` localization file.
+
+#[derive(Debug, Clone, Default)]
+pub struct Locale {
+    language: String,
+    strings: HashMap<u32, String>,
+    skipped_lines: Vec<u64>,
+}
+
+impl Locale {
+    /// Parses the contents of a `.ucs` file as the strings of the given language. The contents
+    /// must be UTF-16 (little endian, as the game ships them), optionally starting with a byte
+    /// order mark. Empty lines are skipped, and a line without a tab is an ID with an empty
+    /// string. Lines whose ID isn't a number are skipped rather than failing the whole file; see
+    /// `Locale::skipped_lines`.
+    pub fn from_ucs_bytes(language: &str, input: &[u8]) -> Result<Locale, LocaleError> {
+        let pairs = input.chunks_exact(2);
+        if !pairs.remainder().is_empty() {
+            return Err(LocaleError::Malformed {
+                reason: "odd number of bytes in UTF-16 input".to_string(),
+            });
+        }
+
+        let units: Vec<u16> = pairs
+            .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+            .collect();
+        let text = String::from_utf16(&units).map_err(|_| LocaleError::Malformed {
+            reason: "invalid UTF-16".to_string(),
+        })?;
+
+        let mut strings = HashMap::new();
+        let mut skipped_lines = Vec::new();
+        for (idx, line) in text.trim_start_matches('\u{feff}').lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let (id, string) = line.split_once('\t').unwrap_or((line, ""));
+            match id.trim().parse() {
+                Ok(id) => {
+                    strings.insert(id, string.to_string());
+                }
+                Err(_) => skipped_lines.push(idx as u64 + 1),
+            }
+        }
+
+        Ok(Locale {
+            language: language.to_string(),
+            strings,
+            skipped_lines,
+        })
+    }
+
+    /// Reads the `.ucs` file at the given path. Failure to read the file will return
+    /// `LocaleError::Io`; any other failures are the same as `Locale::from_ucs_bytes`.
+    pub fn from_ucs_path(language: &str, path: impl AsRef<Path>) -> Result<Locale, LocaleError> {
+        let data = fs::read(path)?;
+        Locale::from_ucs_bytes(language, &data)
+    }
+
+    /// Reads the given reader to completion and parses the result as a `.ucs` file. Failure to
+    /// read will return `LocaleError::Io`; any other failures are the same as
+    /// `Locale::from_ucs_bytes`.
+    pub fn from_ucs_reader(language: &str, mut reader: impl Read) -> Result<Locale, LocaleError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Locale::from_ucs_bytes(language, &data)
+    }
+
+    /// The language these strings are in, as given when the locale was loaded.
+    pub fn language(&self) -> &str {
+        &self.language
+    }
+    /// The number of strings in the locale.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+    /// Whether the locale has no strings.
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+    /// The numbers (starting at 1) of the lines that were skipped while parsing because their ID
+    /// wasn't a number, such as comments or stray text in a hand-edited file.
+    pub fn skipped_lines(&self) -> &[u64] {
+        &self.skipped_lines
+    }
+    /// The string with the given numeric ID, or `None` if the locale doesn't have it.
+    pub fn get(&self, id: u32) -> Option<&str> {
+        self.strings.get(&id).map(String::as_str)
+    }
+    /// The string a localization ID such as `$11233954` refers to. Returns `None` if the ID isn't
+    /// a `$` followed by a number, or if the locale doesn't have it.
+    pub fn resolve(&self, id: &str) -> Option<&str> {
+        id.strip_prefix('$')
+            .and_then(|id| id.parse().ok())
+            .and_then(|id| self.get(id))
+    }
+}
+
+/// Locales for several languages, looked up by language.
+
+#[derive(Debug, Clone, Default)]
+pub struct Locales {
+    locales: HashMap<String, Locale>,
+}
+
+impl Locales {
+    /// Collects the given locales. If several locales share a language, the last one is kept.
+    pub fn new(locales: impl IntoIterator<Item = Locale>) -> Self {
+        Self {
+            locales: locales
+                .into_iter()
+                .map(|locale| (locale.language.clone(), locale))
+                .collect(),
+        }
+    }
+
+    /// Adds a locale, replacing any existing locale for the same language.
+    pub fn insert(&mut self, locale: Locale) {
+        self.locales.insert(locale.language.clone(), locale);
+    }
+    /// The locale for the given language, or `None` if it hasn't been loaded.
+    pub fn get(&self, language: &str) -> Option<&Locale> {
+        self.locales.get(language)
+    }
+    /// The languages that have been loaded, sorted alphabetically.
+    pub fn languages(&self) -> Vec<&str> {
+        let mut languages: Vec<&str> = self.locales.keys().map(String::as_str).collect();
+        languages.sort_unstable();
+        languages
+    }
+    /// The string a localization ID refers to in the given language. See `Locale::resolve`.
+    pub fn resolve(&self, language: &str, id: &str) -> Option<&str> {
+        self.get(language).and_then(|locale| locale.resolve(id))
+    }
+}
+
+/// Represents a failure to load a `.ucs` localization file.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LocaleError {
+    /// The file could not be read from its source. `message` is the description of the
+    /// underlying I/O error.
+    Io {
+        kind: io::ErrorKind,
+        message: String,
+    },
+    /// The file isn't valid UTF-16.
+    Malformed { reason: String },
+}
+
+impl Display for LocaleError {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            LocaleError::Io { message, .. } => {
+                write!(f, "failed to read localization file: {}", message)
+            }
+            LocaleError::Malformed { reason } => {
+                write!(f, "malformed localization file: {}", reason)
+            }
+        }
+    }
+}
+
+impl Error for LocaleError {}
+
+impl From<io::Error> for LocaleError {
+    fn from(err: io::Error) -> Self {
+        LocaleError::Io {
+            kind: err.kind(),
+            message: err.to_string(),
+        }
+    }
+}
@@ -1,6 +1,7 @@
 //! Representation of parsed map information.
 
 use crate::data::chunks::DataSdscChunk;
+use crate::locale::Locale;
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -26,7 +27,7 @@ impl Map {
     }
     /// Entity ID that corresponds to a localization string that represents the localized name of
     /// the map. Conventionally these IDs do not change between patches, but that isn't guaranteed.
-    /// The string is UTF-16 encoded.
+    /// The string is UTF-16 encoded. See `Map::localized_name` to look up the name itself.
     pub fn localized_name_id(&self) -> &str {
         &self.localized_name_id
     }
@@ -36,6 +37,27 @@ impl Map {
     pub fn localized_description_id(&self) -> &str {
         &self.localized_description_id
     }
+    /// The name of the map in the given locale, found by resolving `Map::localized_name_id`. Some
+    /// maps (generally custom ones) store their name directly rather than as a localization ID,
+    /// in which case the name is returned as is. Returns `None` if the locale doesn't have the
+    /// map's name.
+    pub fn localized_name<'a>(&'a self, locale: &'a Locale) -> Option<&'a str> {
+        localize(&self.localized_name_id, locale)
+    }
+    /// The description of the map in the given locale, found by resolving
+    /// `Map::localized_description_id`. See `Map::localized_name` for details.
+    pub fn localized_description<'a>(&'a self, locale: &'a Locale) -> Option<&'a str> {
+        localize(&self.localized_description_id, locale)
+    }
+}
+
+// localization IDs are a $ followed by the numeric ID; anything else is the text itself
+fn localize<'a>(id: &'a str, locale: &'a Locale) -> Option<&'a str> {
+    if id.starts_with('$') {
+        locale.resolve(id)
+    } else {
+        Some(id)
+    }
 }
 
 pub(crate) fn map_from_data(data: &DataSdscChunk) -> Map {
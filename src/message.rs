@@ -1,5 +1,7 @@
 //! Representation of parsed message information.
 
+use crate::game_time::GameTime;
+
 #[cfg(feature = "serde")]
 use serde::{Deserialize, Serialize};
 
@@ -7,24 +9,23 @@ use serde::{Deserialize, Serialize};
 /// collected during command parsing and then associated with the `Player` instance that sent them.
 /// To access, see `Player::messages`.
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Message"))]
 pub struct Message {
-    tick: u32,
+    tick: GameTime,
     message: String,
 }
 
 impl Message {
-    pub(crate) fn new(tick: u32, message: String) -> Self {
+    pub(crate) fn new(tick: GameTime, message: String) -> Self {
         Self { tick, message }
     }
 
-    /// This value is the tick at which the message was found while parsing the replay, which
-    /// represents the time in the replay at which it was sent. Because CoH3's engine runs at 8
-    /// ticks per second, you can divide this value by 8 to get the number of seconds since the
-    /// replay began, which will tell you when this message was sent.
-    pub fn tick(&self) -> u32 {
+    /// This value is the number of command ticks before the message in the replay, which
+    /// represents the time in the replay at which it was sent and lines up with command ticks. See
+    /// `GameTime` for converting it to seconds or formatting it like the in-game clock.
+    pub fn tick(&self) -> GameTime {
         self.tick
     }
     /// UTF-16 encoded representation of the message sent by the player.
@@ -0,0 +1,236 @@
+//! Representation of inferred match outcomes.
+
+use crate::command::Command;
+use crate::game_time::GameTime;
+use crate::player::{Player, Team};
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+// if one team kept issuing commands for at least this long after the other team stopped, the team
+// that stopped is assumed to have lost
+const INACTIVITY_THRESHOLD: GameTime = GameTime::from_secs(30);
+
+/// The result of a match as inferred from a replay. Replays don't record who won, so the outcome
+/// is pieced together from what the players did: surrenders, players leaving the match and being
+/// replaced by AI, and when each team stopped issuing commands. Every outcome carries a
+/// `Confidence` describing how much evidence it was based on. To access, see `Replay::outcome`.
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Outcome"))]
+pub struct Outcome {
+    winner: Option<Team>,
+    reason: EndReason,
+    confidence: Confidence,
+    results: Vec<PlayerResult>,
+}
+
+impl Outcome {
+    /// The team that won the match, or `None` if the winner couldn't be determined.
+    pub fn winner(&self) -> Option<Team> {
+        self.winner
+    }
+    /// How the match ended.
+    pub fn reason(&self) -> EndReason {
+        self.reason
+    }
+    /// How much the outcome can be relied upon. See `Confidence` for details.
+    pub fn confidence(&self) -> Confidence {
+        self.confidence
+    }
+    /// The result of the match for each player, in the same order as `Replay::players`.
+    pub fn results(&self) -> Vec<PlayerResult> {
+        self.results.clone()
+    }
+    /// The result of the match for the player with the given ID, or `None` if no player with that
+    /// ID took part in the match.
+    pub fn result_for(&self, player_id: u32) -> Option<MatchResult> {
+        self.results
+            .iter()
+            .find(|result| result.player_id == player_id)
+            .map(|result| result.result)
+    }
+}
+
+/// The result of a match for a single player.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::PlayerResult"))]
+pub struct PlayerResult {
+    player_id: u32,
+    result: MatchResult,
+}
+
+impl PlayerResult {
+    /// The ID of the player this result belongs to. See `Player::id`.
+    pub fn player_id(&self) -> u32 {
+        self.player_id
+    }
+    /// Whether the player won or lost.
+    pub fn result(&self) -> MatchResult {
+        self.result
+    }
+}
+
+// this is safe as PlayerResult does not contain any Ruby types
+#[cfg(feature = "magnus")]
+unsafe impl magnus::IntoValueFromNative for PlayerResult {}
+
+/// Whether a player won or lost a match.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::MatchResult"))]
+pub enum MatchResult {
+    Win,
+    Loss,
+    /// The winner of the match couldn't be determined.
+    Unknown,
+}
+
+/// How a match ended.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::EndReason"))]
+pub enum EndReason {
+    /// Every human player on the losing team surrendered or left the match, and at least one of
+    /// them surrendered.
+    Surrender,
+    /// The losing team ran out of victory points. Replays don't record victory point totals, so
+    /// this is currently never inferred; matches that ended this way are reported as `Unknown`.
+    VictoryPoints,
+    /// The losing team lost all of its units and structures. Replays don't record this, so it is
+    /// currently never inferred; matches that ended this way are reported as `Unknown`.
+    Annihilation,
+    /// Every human player on the losing team left the match and was replaced by AI.
+    Disconnect,
+    /// The replay doesn't contain enough information to say how the match ended.
+    Unknown,
+}
+
+/// How much evidence an `Outcome` was based on. Confidence levels are ordered, so they can be
+/// compared to filter out outcomes that aren't reliable enough for a given purpose.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Confidence"))]
+pub enum Confidence {
+    /// The outcome is a guess based on when each team stopped issuing commands, or no winner
+    /// could be determined at all.
+    Low,
+    /// The outcome is based on players leaving the match. Players sometimes leave once the match
+    /// is already decided, so this usually but not always identifies the losing team.
+    Medium,
+    /// The outcome is based on the losing team surrendering.
+    High,
+}
+
+pub(crate) fn outcome_from_players(players: &[Player]) -> Outcome {
+    let loser = match (
+        departure(players, Team::First),
+        departure(players, Team::Second),
+    ) {
+        // both teams left the match, so the team that left first is assumed to have lost
+        (Some(first), Some(second)) if second.0 < first.0 => Some((Team::Second, second.1)),
+        (Some(first), _) => Some((Team::First, first.1)),
+        (None, Some(second)) => Some((Team::Second, second.1)),
+        (None, None) => None,
+    };
+    let (winner, reason, confidence) = match loser {
+        Some((team, reason)) => (Some(opponent(team)), reason, confidence_for(reason)),
+        None => (
+            inactivity_winner(players),
+            EndReason::Unknown,
+            Confidence::Low,
+        ),
+    };
+
+    Outcome {
+        winner,
+        reason,
+        confidence,
+        results: players
+            .iter()
+            .map(|player| PlayerResult {
+                player_id: player.id(),
+                result: match winner {
+                    Some(team) if team == player.team() => MatchResult::Win,
+                    Some(_) => MatchResult::Loss,
+                    None => MatchResult::Unknown,
+                },
+            })
+            .collect(),
+    }
+}
+
+// returns the tick at which the last human player on the team surrendered or was replaced by AI,
+// or None if any human player on the team stayed until the end
+fn departure(players: &[Player], team: Team) -> Option<(GameTime, EndReason)> {
+    let humans: Vec<_> = players
+        .iter()
+        .filter(|player| player.human() && player.team() == team)
+        .collect();
+
+    if humans.is_empty() {
+        return None;
+    }
+
+    let ticks = humans
+        .iter()
+        .map(|player| player.surrender_tick().or(player.ai_takeover_tick()))
+        .collect::<Option<Vec<_>>>()?;
+    let reason = if humans
+        .iter()
+        .any(|player| player.surrender_tick().is_some())
+    {
+        EndReason::Surrender
+    } else {
+        EndReason::Disconnect
+    };
+
+    ticks.into_iter().max().map(|tick| (tick, reason))
+}
+
+fn inactivity_winner(players: &[Player]) -> Option<Team> {
+    let first = last_command_tick(players, Team::First)?;
+    let second = last_command_tick(players, Team::Second)?;
+
+    if first >= second + INACTIVITY_THRESHOLD {
+        Some(Team::First)
+    } else if second >= first + INACTIVITY_THRESHOLD {
+        Some(Team::Second)
+    } else {
+        None
+    }
+}
+
+fn last_command_tick(players: &[Player], team: Team) -> Option<GameTime> {
+    players
+        .iter()
+        .filter(|player| player.team() == team)
+        .flat_map(|player| player.commands())
+        .filter(|command| match command {
+            Command::AITakeover(_) => false,
+            Command::Unknown(data) => data.index() != 0,
+            _ => true,
+        })
+        .map(|command| command.tick())
+        .max()
+}
+
+fn confidence_for(reason: EndReason) -> Confidence {
+    match reason {
+        EndReason::Surrender => Confidence::High,
+        _ => Confidence::Medium,
+    }
+}
+
+fn opponent(team: Team) -> Team {
+    match team {
+        Team::First => Team::Second,
+        Team::Second => Team::First,
+    }
+}
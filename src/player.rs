@@ -2,7 +2,11 @@
 
 use crate::command::Command;
 use crate::data::Player as PlayerData;
+use crate::errors::VaultError;
+use crate::game_time::GameTime;
+use crate::loadout::{loadout_item_from_data, LoadoutCategory, LoadoutItem};
 use crate::message::Message;
+use crate::production::{production_from_commands, ProductionItem};
 use std::collections::HashMap;
 use std::convert::TryFrom;
 use std::fmt;
@@ -21,20 +25,35 @@ use serde::{Deserialize, Serialize};
 #[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
 #[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::Player"))]
 pub struct Player {
+    id: u32,
     name: String,
     human: bool,
+    ai_profile: Option<AiProfile>,
+    ai_personality: Option<String>,
+    ai_takeover_tick: Option<GameTime>,
+    surrender_tick: Option<GameTime>,
     faction: Faction,
     team: Team,
     battlegroup: Option<u32>,
     steam_id: Option<u64>,
     profile_id: Option<u64>,
+    loadout: Vec<LoadoutItem>,
     messages: Vec<Message>,
     commands: Vec<Command>,
+    production: Vec<ProductionItem>,
+    unmatched_cancels: Vec<Command>,
+    cancelled_builds: Vec<usize>,
     #[cfg(feature = "raw")]
     raw_commands: Vec<RawCommand>,
 }
 
 impl Player {
+    /// The player's ID within this match, which is determined by the lobby slot they occupied.
+    /// Unlike `Player::steam_id` and `Player::profile_id` this is only unique within a single
+    /// replay, but it's available for AI players too.
+    pub fn id(&self) -> u32 {
+        self.id
+    }
     /// Name of the player at the time the replay was recorded. Note that the player may have
     /// changed their name since time of recording. If attempting to uniquely identify players
     /// across replay files, look at `Player::steam_id` and `Player::profile_id` instead. The string
@@ -46,6 +65,29 @@ impl Player {
     pub fn human(&self) -> bool {
         self.human
     }
+    /// The difficulty of the AI controlling this player, or `None` if the player is human. Note
+    /// that human players who were replaced by AI partway through the match are still considered
+    /// human; see `Player::ai_takeover_tick` to detect those cases.
+    pub fn ai_profile(&self) -> Option<AiProfile> {
+        self.ai_profile
+    }
+    /// The internal name of the personality used by the AI controlling this player, or `None` if
+    /// the player is human. In most matches this is `default_ai_personality`.
+    pub fn ai_personality(&self) -> Option<&str> {
+        self.ai_personality.as_deref()
+    }
+    /// The tick at which AI took control of this player, or `None` if the player was never taken
+    /// over. This is only set for human players, so it can be used to find players who became AI
+    /// partway through the match (for example after disconnecting). AI players issue an
+    /// `AITakeover` command at the start of every match, so they never have this value set; see
+    /// `Player::ai_profile` to identify them instead.
+    pub fn ai_takeover_tick(&self) -> Option<GameTime> {
+        self.ai_takeover_tick
+    }
+    /// The tick at which the player surrendered, or `None` if the player never surrendered.
+    pub fn surrender_tick(&self) -> Option<GameTime> {
+        self.surrender_tick
+    }
     /// The faction selected by the player in this match.
     pub fn faction(&self) -> Faction {
         self.faction
@@ -71,6 +113,11 @@ impl Player {
     pub fn profile_id(&self) -> Option<u64> {
         self.profile_id
     }
+    /// The items the player brought into the match, battlegroups first followed by cosmetics. See
+    /// `LoadoutItem` for details on what information is available for each item.
+    pub fn loadout(&self) -> Vec<LoadoutItem> {
+        self.loadout.clone()
+    }
     /// A list of all messages sent by the player in the match. Sorted chronologically from first
     /// to last.
     pub fn messages(&self) -> Vec<Message> {
@@ -100,12 +147,42 @@ impl Player {
             .filter(|entry| {
                 matches!(
                     entry,
-                    Command::BuildGlobalUpgrade(_) | Command::BuildSquad(_)
+                    Command::BuildGlobalUpgrade(_)
+                        | Command::BuildSquad(_)
+                        | Command::BuildSquadUpgrade(_)
                 )
             })
             .collect()
     }
 
+    /// Every unit and upgrade the player queued in their structures, sorted chronologically, along
+    /// with whether each was later cancelled. See `ProductionItem` for details.
+    pub fn production(&self) -> Vec<ProductionItem> {
+        self.production.clone()
+    }
+
+    /// The player's production cancels that couldn't be linked to anything they queued, sorted
+    /// chronologically from first to last. These are either cancels whose queue entry wasn't found,
+    /// or player level cancels, which are kept as `Command::Unknown` since their layout hasn't been
+    /// decoded. Whatever they cancelled is still part of `Player::effective_build_order`.
+    pub fn unmatched_cancels(&self) -> Vec<Command> {
+        self.unmatched_cancels.clone()
+    }
+
+    /// The player's build commands (see `Player::build_commands`), leaving out any that queued a
+    /// unit or upgrade the player later cancelled. Squad upgrades are left out once every squad
+    /// they were issued to cancelled them. Cancels that can't be linked to a build command (see
+    /// `Player::unmatched_cancels`) don't remove anything. Sorted chronologically from first to
+    /// last.
+    pub fn effective_build_order(&self) -> Vec<Command> {
+        self.build_commands()
+            .into_iter()
+            .enumerate()
+            .filter(|(position, _)| self.cancelled_builds.binary_search(position).is_err())
+            .map(|(_, command)| command)
+            .collect()
+    }
+
     /// A list of only battlegroup-related commands executed by the player in the match. A
     /// battlegroup command is any that involves the select or use of battlegroups and their
     /// abilities.
@@ -118,6 +195,7 @@ impl Player {
                     entry,
                     Command::SelectBattlegroup(_)
                         | Command::SelectBattlegroupAbility(_)
+                        | Command::DeselectBattlegroupAbilities(_)
                         | Command::UseBattlegroupAbility(_)
                 )
             })
@@ -127,19 +205,49 @@ impl Player {
 
 pub(crate) fn player_from_data(
     player_data: &PlayerData,
+    path: &str,
     messages: &HashMap<String, Vec<Message>>,
     commands: &HashMap<u32, Vec<Command>>,
     #[cfg(feature = "raw")] raw_commands: &HashMap<u32, Vec<RawCommand>>,
-) -> Player {
+) -> Result<Player, VaultError> {
     let mut player = Player {
+        id: player_data.id,
         name: player_data.name.clone(),
         human: player_data.human != 0,
-        faction: Faction::try_from(player_data.faction.as_ref()).unwrap(),
-        team: Team::try_from(player_data.team).unwrap(),
+        ai_profile: None,
+        ai_personality: None,
+        ai_takeover_tick: None,
+        surrender_tick: None,
+        faction: Faction::try_from(player_data.faction.as_ref()).map_err(|_| {
+            VaultError::UnknownFaction {
+                offset: player_data.offset,
+                path: path.to_string(),
+                faction: player_data.faction.clone(),
+            }
+        })?,
+        team: Team::try_from(player_data.team).map_err(|_| VaultError::UnknownTeam {
+            offset: player_data.offset,
+            path: path.to_string(),
+            team: player_data.team,
+        })?,
         steam_id: None,
         profile_id: None,
+        loadout: player_data
+            .battlegroup_items
+            .iter()
+            .map(|item| loadout_item_from_data(item, LoadoutCategory::Battlegroup))
+            .chain(
+                player_data
+                    .cosmetic_items
+                    .iter()
+                    .map(|item| loadout_item_from_data(item, LoadoutCategory::Cosmetic)),
+            )
+            .collect(),
         messages: messages.get(&player_data.name).cloned().unwrap_or_default(),
         commands: commands.get(&player_data.id).cloned().unwrap_or_default(),
+        production: Vec::new(),
+        unmatched_cancels: Vec::new(),
+        cancelled_builds: Vec::new(),
         #[cfg(feature = "raw")]
         raw_commands: raw_commands
             .get(&player_data.id)
@@ -149,21 +257,40 @@ pub(crate) fn player_from_data(
     };
 
     if player.human {
-        player.steam_id = Some(str::parse(&player_data.steam_id).unwrap());
+        player.steam_id =
+            Some(
+                str::parse(&player_data.steam_id).map_err(|_| VaultError::InvalidSteamId {
+                    offset: player_data.offset,
+                    path: path.to_string(),
+                    steam_id: player_data.steam_id.clone(),
+                })?,
+            );
         player.profile_id = Some(player_data.profile_id);
+        player.ai_takeover_tick = player.commands.iter().find_map(|command| match command {
+            Command::AITakeover(command) => Some(command.tick()),
+            _ => None,
+        });
+    } else {
+        player.ai_profile = Some(AiProfile::from(player_data.ai_difficulty));
+        player.ai_personality = Some(player_data.ai_type.clone());
     }
 
-    player.battlegroup = match player
-        .commands
-        .iter()
-        .find(|&command| matches!(command, Command::SelectBattlegroup(_)))
-    {
-        Some(Command::SelectBattlegroup(command)) => Some(command.pbgid()),
-        Some(_) => panic!(),
-        None => None,
-    };
+    player.surrender_tick = player.commands.iter().find_map(|command| match command {
+        Command::Surrender(command) => Some(command.tick()),
+        _ => None,
+    });
+
+    player.battlegroup = player.commands.iter().find_map(|command| match command {
+        Command::SelectBattlegroup(command) => Some(command.pbgid()),
+        _ => None,
+    });
+
+    let production = production_from_commands(&player.commands);
+    player.production = production.items;
+    player.unmatched_cancels = production.unmatched_cancels;
+    player.cancelled_builds = production.cancelled_builds;
 
-    player
+    Ok(player)
 }
 
 impl Display for Player {
@@ -214,6 +341,44 @@ impl TryFrom<&str> for Faction {
     }
 }
 
+/// Difficulty levels available to AI players.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::AiProfile"))]
+pub enum AiProfile {
+    Easy,
+    Standard,
+    Hard,
+    Expert,
+    /// A difficulty value that doesn't match any known level
+    Unknown,
+}
+
+impl Display for AiProfile {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            AiProfile::Easy => write!(f, "easy"),
+            AiProfile::Standard => write!(f, "standard"),
+            AiProfile::Hard => write!(f, "hard"),
+            AiProfile::Expert => write!(f, "expert"),
+            AiProfile::Unknown => write!(f, "unknown"),
+        }
+    }
+}
+
+impl From<u32> for AiProfile {
+    fn from(input: u32) -> AiProfile {
+        match input {
+            0 => AiProfile::Easy,
+            1 => AiProfile::Standard,
+            2 => AiProfile::Hard,
+            3 => AiProfile::Expert,
+            _ => AiProfile::Unknown,
+        }
+    }
+}
+
 /// Representation of a player's team membership.
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
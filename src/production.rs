@@ -0,0 +1,167 @@
+//! Representation of production queues.
+
+use crate::command::Command;
+use crate::command_data::SourcedPbgid;
+use crate::command_type::CommandType;
+use crate::game_time::GameTime;
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A unit or upgrade a player queued for production in one of their structures, along with
+/// whether it was later cancelled. Replays only record the commands issued, so items are rebuilt by
+/// replaying each structure's build queue: every build command is given the next queue index for
+/// its source structure, and cancellations refer back to it by that index. To access, see
+/// `Player::production`.
+///
+/// Note that an item that wasn't cancelled isn't necessarily completed, since the replay doesn't
+/// record when a structure is destroyed or a player runs out of resources. Upgrades queued on
+/// squads aren't included, though `Player::effective_build_order` accounts for their cancellation.
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::ProductionItem"))]
+pub struct ProductionItem {
+    build: SourcedPbgid,
+    global_upgrade: bool,
+    queue_index: u32,
+    cancel_tick: Option<GameTime>,
+}
+
+impl ProductionItem {
+    /// The tick at which the item was queued.
+    pub fn tick(&self) -> GameTime {
+        self.build.tick()
+    }
+    /// The build command that queued the item, which is either a `Command::BuildSquad` or a
+    /// `Command::BuildGlobalUpgrade`.
+    pub fn command(&self) -> Command {
+        if self.global_upgrade {
+            Command::BuildGlobalUpgrade(self.build)
+        } else {
+            Command::BuildSquad(self.build)
+        }
+    }
+    /// The data of the build command that queued the item. See `ProductionItem::command`.
+    pub fn build(&self) -> SourcedPbgid {
+        self.build
+    }
+    /// The pbgid of the queued squad or upgrade.
+    pub fn pbgid(&self) -> u32 {
+        self.build.pbgid()
+    }
+    /// The identifier of the structure the item was queued in. See
+    /// `SourcedPbgid::source_identifier`.
+    pub fn source_identifier(&self) -> u16 {
+        self.build.source_identifier()
+    }
+    /// The index the item was given in its source structure's build queue. See
+    /// `SourcedIndex::queue_index`.
+    pub fn queue_index(&self) -> u32 {
+        self.queue_index
+    }
+    /// Whether the player cancelled the item.
+    pub fn cancelled(&self) -> bool {
+        self.cancel_tick.is_some()
+    }
+    /// The tick at which the item was cancelled, or `None` if it wasn't.
+    pub fn cancel_tick(&self) -> Option<GameTime> {
+        self.cancel_tick
+    }
+}
+
+// this is safe as ProductionItem does not contain any Ruby types
+#[cfg(feature = "magnus")]
+unsafe impl magnus::IntoValueFromNative for ProductionItem {}
+
+// the result of replaying a player's build and cancel commands. cancelled builds are identified by
+// their position in `Player::build_commands`, since command indexes aren't unique. squad upgrades
+// are tracked separately from structure queues, since squad queues also count items the replay
+// doesn't record and so can't be matched by queue index
+pub(crate) struct Production {
+    pub items: Vec<ProductionItem>,
+    pub cancelled_builds: Vec<usize>,
+    pub unmatched_cancels: Vec<Command>,
+}
+
+pub(crate) fn production_from_commands(commands: &[Command]) -> Production {
+    let mut items: Vec<ProductionItem> = Vec::new();
+    let mut item_builds: Vec<usize> = Vec::new();
+    let mut builds = 0;
+    let mut queue_indexes: HashMap<u16, u32> = HashMap::new();
+    let mut queued: HashMap<(u16, u32), usize> = HashMap::new();
+    // build position and number of squads still working on each squad upgrade, along with the
+    // upgrades pending on each squad from oldest to newest
+    let mut squad_upgrades: Vec<(usize, usize)> = Vec::new();
+    let mut pending: HashMap<u32, Vec<usize>> = HashMap::new();
+    let mut cancelled_builds = Vec::new();
+    let mut unmatched_cancels = Vec::new();
+
+    for command in commands {
+        match command {
+            Command::BuildSquad(data) | Command::BuildGlobalUpgrade(data) => {
+                let queue_index = queue_indexes.entry(data.source_identifier()).or_insert(0);
+                *queue_index += 1;
+                queued.insert((data.source_identifier(), *queue_index), items.len());
+                items.push(ProductionItem {
+                    build: *data,
+                    global_upgrade: matches!(command, Command::BuildGlobalUpgrade(_)),
+                    queue_index: *queue_index,
+                    cancel_tick: None,
+                });
+                item_builds.push(builds);
+                builds += 1;
+            }
+            Command::BuildSquadUpgrade(data) => {
+                for squad_id in data.squad_ids() {
+                    pending
+                        .entry(*squad_id)
+                        .or_default()
+                        .push(squad_upgrades.len());
+                }
+                squad_upgrades.push((builds, data.squad_ids().len()));
+                builds += 1;
+            }
+            Command::CancelProduction(data) => {
+                match queued.remove(&(data.source_identifier(), data.queue_index())) {
+                    Some(idx) => {
+                        items[idx].cancel_tick = Some(data.tick());
+                        cancelled_builds.push(item_builds[idx]);
+                    }
+                    None => unmatched_cancels.push(command.clone()),
+                }
+            }
+            // the cancel is assumed to refer to the latest upgrade still pending on the squad,
+            // since its queue index can't be used
+            Command::CancelSquadProduction(data) => {
+                let mut matched = false;
+                for squad_id in data.squad_ids() {
+                    if let Some(idx) = pending.get_mut(squad_id).and_then(|pending| pending.pop()) {
+                        matched = true;
+                        let (build, remaining) = &mut squad_upgrades[idx];
+                        *remaining -= 1;
+                        if *remaining == 0 {
+                            cancelled_builds.push(*build);
+                        }
+                    }
+                }
+                if !matched {
+                    unmatched_cancels.push(command.clone());
+                }
+            }
+            // player level cancels aren't decoded, so there's nothing to link them to
+            Command::Unknown(data) if data.action_type() == CommandType::PCMD_CancelProduction => {
+                unmatched_cancels.push(command.clone())
+            }
+            _ => {}
+        }
+    }
+
+    cancelled_builds.sort_unstable();
+    Production {
+        items,
+        cancelled_builds,
+        unmatched_cancels,
+    }
+}
@@ -1,14 +1,24 @@
 //! Representation of parsed replay information.
 
-use crate::data::chunks::DataAutoChunk;
+use crate::capture::{capture_timeline_from_players, StrategicPoint};
+use crate::data::chunks::{DataAutoChunk, DataDataChunk};
 use crate::data::{Replay as ReplayData, Span};
+use crate::errors::VaultError;
+use crate::event::{event_sources_from_data, EventSource, Events};
+use crate::game_time::GameTime;
+use crate::integrity::{integrity_report_from_replay, IntegrityReport};
 use crate::map::{map_from_data, Map};
+use crate::outcome::{outcome_from_players, Outcome};
 use crate::player::{player_from_data, Player};
-use crate::ParseError;
+use crate::settings::{settings_from_data, MatchSettings};
+use crate::summary::{summary_from_data, ReplaySummary};
 use nom_locate::LocatedSpan;
 use nom_tracable::TracableInfo;
 use std::fmt;
 use std::fmt::{Display, Formatter};
+use std::fs;
+use std::io::Read;
+use std::path::Path;
 use uuid::Uuid;
 
 #[cfg(feature = "serde")]
@@ -28,8 +38,24 @@ pub struct Replay {
     matchhistory_id: Option<u64>,
     mod_uuid: Uuid,
     map: Map,
+    settings: MatchSettings,
     players: Vec<Player>,
+    player_ids: Vec<u32>,
+    matchtype_id: Option<u64>,
+    recorder_id: Option<u32>,
+    // crate-private until their meaning is known, see the accessors below
+    #[cfg_attr(feature = "serde", serde(skip))]
+    map_grid_kind: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    map_grid_hash: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    save_parameters: Option<u32>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    locale_flag: Option<u32>,
     length: usize,
+    // merge order for `events`, an implementation detail rather than replay data
+    #[cfg_attr(feature = "serde", serde(skip))]
+    event_sources: Vec<EventSource>,
 }
 
 impl Replay {
@@ -43,11 +69,79 @@ impl Replay {
     ///     assert!(replay.is_ok())
     /// }
     /// ```
-    pub fn from_bytes(input: &[u8]) -> Result<Replay, ParseError<'_>> {
-        let info = TracableInfo::new().parser_width(64).fold("term");
-        let input: Span = LocatedSpan::new_extra(input, info);
-        let (_, replay) = ReplayData::from_span(input)?;
-        Ok(replay_from_data(&replay))
+    pub fn from_bytes(input: &[u8]) -> Result<Replay, VaultError> {
+        let (_, replay) = ReplayData::from_span(span_from_bytes(input))
+            .map_err(|err| VaultError::from_nom(err, input.len()))?;
+        replay_from_data(&replay)
+    }
+
+    /// Reads the file at the given path in its entirety, parses it as a CoH3 replay, and returns a
+    /// representation of the parsed information. Failure to read the file will return
+    /// `VaultError::Io`; any other failures are the same as `Replay::from_bytes`.
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let replay = vault::Replay::from_path("/path/to/replay.rec");
+    ///     assert!(replay.is_ok())
+    /// }
+    /// ```
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Replay, VaultError> {
+        let data = fs::read(path)?;
+        Replay::from_bytes(&data)
+    }
+
+    /// Reads the given reader to completion, parses the result as a CoH3 replay, and returns a
+    /// representation of the parsed information. Failure to read will return `VaultError::Io`; any
+    /// other failures are the same as `Replay::from_bytes`.
+    pub fn from_reader(mut reader: impl Read) -> Result<Replay, VaultError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        Replay::from_bytes(&data)
+    }
+
+    /// Memory maps the file at the given path and parses it as a CoH3 replay, avoiding a copy of
+    /// the file's contents into memory. Requires the `mmap` feature.
+    ///
+    /// Note that the file must not be modified by another process while it's being parsed; doing
+    /// so is undefined behaviour. If that can't be guaranteed, use `Replay::from_path` instead.
+    #[cfg(feature = "mmap")]
+    pub fn from_path_mmap(path: impl AsRef<Path>) -> Result<Replay, VaultError> {
+        let file = fs::File::open(path)?;
+        // safety: see the caveat in this function's documentation
+        let data = unsafe { memmap2::Mmap::map(&file)? };
+        Replay::from_bytes(&data)
+    }
+
+    /// Takes a byte slice and parses only the header and chunk sections of it as a CoH3 replay,
+    /// skipping command and message data entirely. This is much faster than `Replay::from_bytes`
+    /// when all that's needed is basic match information like version, map, players, or match ID.
+    ///
+    /// If `count_ticks` is true, the tick section is also scanned (without being decoded) so that
+    /// `ReplaySummary::length` can be reported.
+    ///
+    /// ```ignore
+    /// fn main() {
+    ///     let data = include_bytes!("/path/to/replay.rec");
+    ///     let summary = vault::Replay::summary_from_bytes(data, false);
+    ///     assert!(summary.is_ok())
+    /// }
+    /// ```
+    pub fn summary_from_bytes(
+        input: &[u8],
+        count_ticks: bool,
+    ) -> Result<ReplaySummary, VaultError> {
+        let (rest, replay) = ReplayData::parse_chunks(span_from_bytes(input))
+            .map_err(|err| VaultError::from_nom(err, input.len()))?;
+
+        let length = if count_ticks {
+            let (_, length) = ReplayData::count_command_ticks(rest)
+                .map_err(|err| VaultError::from_nom(err, input.len()))?;
+            Some(length)
+        } else {
+            None
+        };
+
+        summary_from_data(&replay, length)
     }
 
     /// The Company of Heroes 3 game version this replay was recorded on. Note that this is probably
@@ -103,54 +197,175 @@ impl Replay {
     pub fn map_localized_description_id(&self) -> &str {
         self.map.localized_description_id()
     }
+    /// Lobby options that were configured for this match, such as resource, victory point, and
+    /// population cap settings.
+    pub fn settings(&self) -> MatchSettings {
+        self.settings.clone()
+    }
     /// A list of all players who participated in this match.
     pub fn players(&self) -> Vec<Player> {
         self.players.clone()
     }
-    /// A simple count of the number of ticks that were executed in this match. Because CoH3's
-    /// engine runs at 8 ticks per second, you can divide this value by 8 to get the duration of
-    /// the match in seconds.
+    /// The IDs of every player slot that was connected to this match, in the order the game
+    /// recorded them. These match `Player::id`. Note that this order isn't the same as the order
+    /// of `Replay::players`.
+    pub fn player_ids(&self) -> Vec<u32> {
+        self.player_ids.clone()
+    }
+    /// The ID Relic uses for the type of match that was played, which matches the `matchtype_id`
+    /// returned by Relic's CoH3 stats API. For example, custom games are 0, while ranked 1v1
+    /// through 4v4 automatch games are 20 through 23. This is `None` for replays recorded before
+    /// the replay system release in patch 1.4.0, which didn't include this information.
+    pub fn matchtype_id(&self) -> Option<u64> {
+        self.matchtype_id
+    }
+    /// The ID of the player who recorded this replay, which can be matched against `Player::id`.
+    /// This is `None` for replays recorded before the replay system release in patch 1.4.0, which
+    /// didn't include this information.
+    pub fn recorder_id(&self) -> Option<u32> {
+        self.recorder_id
+    }
+    /// The player whose client recorded this replay. Useful for attributing replays of the same
+    /// match collected from different players. This is `None` if the replay doesn't record who
+    /// made it (see `Replay::recorder_id`), or if the recorder isn't in the player list.
+    pub fn recorded_by(&self) -> Option<&Player> {
+        self.recorder_id
+            .and_then(|id| self.players.iter().find(|player| player.id() == id))
+    }
+    /// The inferred result of the match: which team won, how the match ended, and how confident
+    /// that inference is. Replays don't record the result of a match directly, so see `Outcome`
+    /// for details on how it is determined.
+    pub fn outcome(&self) -> Outcome {
+        outcome_from_players(&self.players)
+    }
+    /// The capture orders issued during the match, grouped by the strategic point they targeted.
+    /// Points are sorted by when they were first ordered to be captured, which shows how early
+    /// each sector was contested. See `StrategicPoint` for details.
+    pub fn capture_timeline(&self) -> Vec<StrategicPoint> {
+        capture_timeline_from_players(&self.players)
+    }
+    /// Every command and chat message from every player, in the order they appear in the replay
+    /// file. Commands the game issued on a player's behalf are yielded as
+    /// `EventKind::SystemCommand`. The returned iterator can be narrowed down to a window of time,
+    /// a single player, or a category of command; see `Events` for details.
+    pub fn events(&self) -> Events<'_> {
+        Events::new(&self.event_sources, &self.players)
+    }
+    /// Checks the replay for developer cheats, mods that aren't in `approved_mods`, and
+    /// inconsistencies in the replay's data. Unmodded matches (a nil mod UUID) are always
+    /// considered approved. See `IntegrityReport` for details.
+    pub fn integrity_report(&self, approved_mods: &[Uuid]) -> IntegrityReport {
+        integrity_report_from_replay(self, approved_mods)
+    }
+    /// Whether the replay fails any integrity check when no mods are approved; that is, whether
+    /// cheats were used, the match was modded, or the replay's data is inconsistent. Use
+    /// `Replay::integrity_report` to approve specific mods or to find out which checks failed.
+    pub fn is_tainted(&self) -> bool {
+        self.integrity_report(&[]).is_tainted()
+    }
+    /// A simple count of the number of ticks that were executed in this match. See
+    /// `Replay::duration` for the same value as a `GameTime`.
     pub fn length(&self) -> usize {
         self.length
     }
+    /// How long the match lasted, which can be converted to seconds or formatted like the in-game
+    /// clock. See `GameTime` for details.
+    pub fn duration(&self) -> GameTime {
+        GameTime::from_ticks(self.length as u32)
+    }
 }
 
-fn replay_from_data(data: &ReplayData) -> Replay {
-    let commands = data.commands();
+// values from the GRIF, SAVP and LOCS chunks whose meaning hasn't been worked out yet. they're kept
+// out of the public API until it has, but are parsed so the chunks are validated
+#[allow(dead_code)]
+impl Replay {
+    /// The first value of the map grid information recorded with the replay. Its meaning isn't
+    /// known, but it is 2 in local skirmish games and 1 otherwise. This is `None` if the replay
+    /// doesn't include map grid information, or if it couldn't be parsed.
+    pub(crate) fn map_grid_kind(&self) -> Option<u32> {
+        self.map_grid_kind
+    }
+    /// The second value of the map grid information recorded with the replay, which looks like a
+    /// hash. It differs between matches played on the same map, so it isn't a hash of the map
+    /// alone. This is `None` under the same conditions as `Replay::map_grid_kind`.
+    pub(crate) fn map_grid_hash(&self) -> Option<u32> {
+        self.map_grid_hash
+    }
+    /// The save parameters recorded with the replay. Their meaning isn't known, and they have been
+    /// 0 in every replay seen so far. This is `None` if the replay doesn't include save
+    /// parameters, or if they couldn't be parsed.
+    pub(crate) fn save_parameters(&self) -> Option<u32> {
+        self.save_parameters
+    }
+    /// The locale information recorded with the replay. Its meaning isn't known; it has been
+    /// either 0 or 1 in every replay seen so far. This is `None` for replays recorded before the
+    /// replay system release in patch 1.4.0, which didn't include this information, or if it
+    /// couldn't be parsed.
+    pub(crate) fn locale_flag(&self) -> Option<u32> {
+        self.locale_flag
+    }
+}
+
+fn span_from_bytes(input: &[u8]) -> Span<'_> {
+    let info = TracableInfo::new().parser_width(64).fold("term");
+    LocatedSpan::new_extra(input, info)
+}
+
+fn replay_from_data(data: &ReplayData) -> Result<Replay, VaultError> {
+    let game_data = data.game_data()?;
+    let commands = data.commands()?;
     let messages = data.messages();
     #[cfg(feature = "raw")]
     let raw_commands = data.raw_commands();
 
-    Replay {
+    let players: Vec<Player> = game_data
+        .players
+        .iter()
+        .map(|player| {
+            player_from_data(
+                player,
+                &game_data.header.path,
+                &messages,
+                &commands,
+                #[cfg(feature = "raw")]
+                &raw_commands,
+            )
+        })
+        .collect::<Result<_, _>>()?;
+    let event_sources = event_sources_from_data(data, &players);
+
+    Ok(Replay {
         version: data.header.version,
         timestamp: data.header.timestamp.clone(),
-        game_type: game_type_from_data(data),
-        matchhistory_id: matchhistory_id_from_data(data),
-        mod_uuid: data.game_data().mod_uuid,
-        map: map_from_data(data.map_data()),
+        game_type: game_type_from_data(data, game_data),
+        matchhistory_id: matchhistory_id_from_data(data, game_data),
+        mod_uuid: game_data.mod_uuid,
+        map: map_from_data(data.map_data()?),
+        settings: settings_from_data(game_data),
         length: data.command_ticks().count(),
-        players: data
-            .game_data()
-            .players
-            .iter()
-            .map(|player| {
-                player_from_data(
-                    player,
-                    &messages,
-                    &commands,
-                    #[cfg(feature = "raw")]
-                    &raw_commands,
-                )
-            })
-            .collect(),
-    }
+        player_ids: data
+            .player_list_data()
+            .map(|chunk| chunk.player_ids.clone())
+            .unwrap_or_default(),
+        matchtype_id: data.match_type_data().map(|chunk| chunk.matchtype_id),
+        recorder_id: data.recorder_data().map(|chunk| chunk.player_id),
+        map_grid_kind: data.map_grid_data().map(|chunk| chunk.kind),
+        map_grid_hash: data.map_grid_data().map(|chunk| chunk.hash),
+        save_parameters: data.save_parameter_data().map(|chunk| chunk.value),
+        locale_flag: data.locale_data().map(|chunk| chunk.value),
+        players,
+        event_sources,
+    })
 }
 
-fn matchhistory_id_from_data(data: &ReplayData) -> Option<u64> {
-    if game_type_from_data(data) == GameType::Skirmish {
+pub(crate) fn matchhistory_id_from_data(
+    data: &ReplayData,
+    game_data: &DataDataChunk,
+) -> Option<u64> {
+    if game_type_from_data(data, game_data) == GameType::Skirmish {
         None
     } else {
-        Some(data.game_data().matchhistory_id)
+        Some(game_data.matchhistory_id)
     }
 }
 
@@ -183,8 +398,8 @@ impl Display for GameType {
     }
 }
 
-fn game_type_from_data(data: &ReplayData) -> GameType {
-    if data.game_data().skirmish {
+pub(crate) fn game_type_from_data(data: &ReplayData, game_data: &DataDataChunk) -> GameType {
+    if game_data.skirmish {
         GameType::Skirmish
     } else {
         match data.automatch_data() {
@@ -0,0 +1,120 @@
+//! Representation of parsed lobby match settings.
+
+use crate::data::chunks::DataDataChunk;
+use std::collections::HashMap;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// Lobby options that were configured for a match. Options are recorded in replays as named integer
+/// values grouped into sections; this type exposes typed accessors for the options whose meaning is
+/// known, while all options (including those without a dedicated accessor) can be looked up by name
+/// via `MatchSettings::get` or `MatchSettings::options`.
+///
+/// Note that many values are the index of the selection made in the lobby dropdown rather than the
+/// literal value of the setting, and which options are present varies with game type and mods.
+
+#[derive(Debug, Clone, Default)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::MatchSettings"))]
+pub struct MatchSettings {
+    options: HashMap<String, u32>,
+    win_condition: Option<WinCondition>,
+}
+
+impl MatchSettings {
+    /// The resource setting selected for the match (`option_resources`). In standard matches this
+    /// is 0.
+    pub fn resources(&self) -> Option<u32> {
+        self.get("option_resources")
+    }
+    /// The number of victory points each team starts with (`option_tickets`). In standard matches
+    /// this is 500.
+    pub fn victory_points(&self) -> Option<u32> {
+        self.get("option_tickets")
+    }
+    /// Index of the starting resources override selected for a custom or skirmish match
+    /// (`starting_resources`).
+    pub fn starting_resources(&self) -> Option<u32> {
+        self.get("starting_resources")
+    }
+    /// The population cap override selected for a custom or skirmish match
+    /// (`population_cap_override`).
+    pub fn population_cap(&self) -> Option<u32> {
+        self.get("population_cap_override")
+    }
+    /// The number of commander points players start with in a custom or skirmish match
+    /// (`starting_commander_points`).
+    pub fn starting_commander_points(&self) -> Option<u32> {
+        self.get("starting_commander_points")
+    }
+    /// Index of the resource income rate selected for a custom or skirmish match
+    /// (`resource_income_percentage`).
+    pub fn resource_income(&self) -> Option<u32> {
+        self.get("resource_income_percentage")
+    }
+    /// Index of the production speed selected for a custom or skirmish match
+    /// (`production_speed_percentage`).
+    pub fn production_speed(&self) -> Option<u32> {
+        self.get("production_speed_percentage")
+    }
+    /// Index of the construction speed selected for a custom or skirmish match
+    /// (`construction_speed_percentage`).
+    pub fn construction_speed(&self) -> Option<u32> {
+        self.get("construction_speed_percentage")
+    }
+    /// Whether fog of war was enabled, if the match recorded the setting
+    /// (`is_fog_of_war_enabled`).
+    pub fn fog_of_war(&self) -> Option<bool> {
+        self.get("is_fog_of_war_enabled").map(|value| value != 0)
+    }
+    /// The win condition the match was played with, inferred from the section its options were
+    /// recorded under. `None` if the options don't include a known win condition section.
+    pub fn win_condition(&self) -> Option<WinCondition> {
+        self.win_condition
+    }
+    /// Look up the value of any option by its internal name.
+    pub fn get(&self, name: &str) -> Option<u32> {
+        self.options.get(name).copied()
+    }
+    /// All options recorded for the match, keyed by internal name.
+    pub fn options(&self) -> &HashMap<String, u32> {
+        &self.options
+    }
+}
+
+/// The condition a team has to meet to win a match. To access, see `MatchSettings::win_condition`.
+/// Only victory points have been seen in replays so far; more win conditions may be added in future
+/// versions.
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::WinCondition"))]
+#[non_exhaustive]
+pub enum WinCondition {
+    /// Teams lose victory points while the other team holds more victory point sectors, and lose
+    /// the match when they run out (`section_tickets`). See `MatchSettings::victory_points` for
+    /// the number of points each team starts with.
+    VictoryPoints,
+}
+
+// options are stored as a section entry whose value is the number of options that follow it
+pub(crate) fn settings_from_data(data: &DataDataChunk) -> MatchSettings {
+    let mut options = HashMap::new();
+    let mut win_condition = None;
+    let mut entries = data.options.iter();
+
+    while let Some(section) = entries.next() {
+        if section.name == "section_tickets" {
+            win_condition = Some(WinCondition::VictoryPoints);
+        }
+        for option in entries.by_ref().take(section.value as usize) {
+            options.insert(option.name.clone(), option.value);
+        }
+    }
+
+    MatchSettings {
+        options,
+        win_condition,
+    }
+}
@@ -0,0 +1,115 @@
+//! Representation of summary replay information parsed without decoding ticks.
+
+use crate::data::Replay as ReplayData;
+use crate::errors::VaultError;
+use crate::map::{map_from_data, Map};
+use crate::player::{player_from_data, Player};
+use crate::replay::{game_type_from_data, matchhistory_id_from_data, GameType};
+use crate::settings::{settings_from_data, MatchSettings};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+/// A lightweight representation of a Company of Heroes 3 replay that only includes information
+/// found before the tick section of the file. Because no commands or messages are decoded, players
+/// in a summary will always have empty command and message lists, no battlegroup, and no AI
+/// takeover or surrender tick. To get this information, parse the full replay with
+/// `Replay::from_bytes` instead.
+///
+/// To create a summary, see `Replay::summary_from_bytes`.
+
+#[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+#[cfg_attr(feature = "magnus", magnus::wrap(class = "VaultCoh::ReplaySummary"))]
+pub struct ReplaySummary {
+    version: u16,
+    timestamp: String,
+    game_type: GameType,
+    matchhistory_id: Option<u64>,
+    mod_uuid: Uuid,
+    map: Map,
+    settings: MatchSettings,
+    players: Vec<Player>,
+    length: Option<usize>,
+}
+
+impl ReplaySummary {
+    /// The Company of Heroes 3 game version this replay was recorded on. See `Replay::version` for
+    /// more information.
+    pub fn version(&self) -> u16 {
+        self.version
+    }
+    /// A UTF-16 representation of the recording user's local time when the replay was recorded.
+    /// See `Replay::timestamp` for more information.
+    pub fn timestamp(&self) -> &str {
+        &self.timestamp
+    }
+    /// The type of game this replay represents. See `Replay::game_type` for more information.
+    pub fn game_type(&self) -> GameType {
+        self.game_type
+    }
+    /// The ID used by Relic to track this match on their internal servers. See
+    /// `Replay::matchhistory_id` for more information.
+    pub fn matchhistory_id(&self) -> Option<u64> {
+        self.matchhistory_id
+    }
+    /// The UUID of the base game mod this replay ran on. See `Replay::mod_uuid` for more
+    /// information.
+    pub fn mod_uuid(&self) -> Uuid {
+        self.mod_uuid
+    }
+    /// Map information for this match.
+    pub fn map(&self) -> Map {
+        self.map.clone()
+    }
+    /// Lobby options that were configured for this match. See `Replay::settings` for more
+    /// information.
+    pub fn settings(&self) -> MatchSettings {
+        self.settings.clone()
+    }
+    /// A list of all players who participated in this match. Note that command and message
+    /// information is not available on summary players.
+    pub fn players(&self) -> Vec<Player> {
+        self.players.clone()
+    }
+    /// A simple count of the number of ticks that were executed in this match, or `None` if tick
+    /// counting wasn't requested when the summary was parsed. See `Replay::length` for more
+    /// information.
+    pub fn length(&self) -> Option<usize> {
+        self.length
+    }
+}
+
+pub(crate) fn summary_from_data(
+    data: &ReplayData,
+    length: Option<usize>,
+) -> Result<ReplaySummary, VaultError> {
+    let game_data = data.game_data()?;
+
+    Ok(ReplaySummary {
+        version: data.header.version,
+        timestamp: data.header.timestamp.clone(),
+        game_type: game_type_from_data(data, game_data),
+        matchhistory_id: matchhistory_id_from_data(data, game_data),
+        mod_uuid: game_data.mod_uuid,
+        map: map_from_data(data.map_data()?),
+        settings: settings_from_data(game_data),
+        length,
+        players: game_data
+            .players
+            .iter()
+            .map(|player| {
+                player_from_data(
+                    player,
+                    &game_data.header.path,
+                    &HashMap::new(),
+                    &HashMap::new(),
+                    #[cfg(feature = "raw")]
+                    &HashMap::new(),
+                )
+            })
+            .collect::<Result<_, _>>()?,
+    })
+}
@@ -7,9 +7,20 @@ use std::{
     fs::{self, File},
     io::Read,
     thread,
+    time::Duration,
 };
 use uuid::{uuid, Uuid};
-use vault::{Command, CommandType, Faction, GameType, Replay, Team};
+use vault::analytics::apm::{self, ApmOptions};
+use vault::attributes::{Attribute, AttributeCategory, Attributes, Cost};
+#[cfg(feature = "attributes")]
+use vault::attributes::{AttributeArchive, AttributeDb, AttributeError};
+use vault::command_data::{CheatKind, Target};
+use vault::locale::{Locale, LocaleError, Locales};
+use vault::{
+    AiProfile, Command, CommandCategory, CommandType, Confidence, EndReason, Event, EventKind,
+    Faction, GameTime, GameType, LoadoutCatalog, LoadoutCategory, MatchResult, MatchSettings,
+    Replay, Team, VaultError, WinCondition, TICKS_PER_SECOND,
+};
 
 #[test]
 fn parse_success() {
@@ -31,6 +42,62 @@ fn parse_success() {
     assert_eq!(unwrapped.matchhistory_id(), Some(5569487));
 }
 
+#[test]
+fn parse_from_path() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    assert_eq!(replay.version(), 10612);
+}
+
+#[test]
+fn parse_from_reader() {
+    let file = File::open("replays/USvDAK_v10612.rec").unwrap();
+    let replay = Replay::from_reader(file).unwrap();
+    assert_eq!(replay.version(), 10612);
+}
+
+#[test]
+#[cfg(feature = "mmap")]
+fn parse_from_path_mmap() {
+    let replay = Replay::from_path_mmap("replays/USvDAK_v10612.rec").unwrap();
+    assert_eq!(replay.version(), 10612);
+}
+
+#[test]
+fn parse_missing_file() {
+    let err = Replay::from_path("replays/does_not_exist.rec").unwrap_err();
+    assert!(matches!(
+        err,
+        VaultError::Io {
+            kind: std::io::ErrorKind::NotFound,
+            ..
+        }
+    ));
+    assert_eq!(err.offset(), 0);
+    assert_eq!(err.path(), "");
+}
+
+#[test]
+fn parse_summary() {
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let summary = Replay::summary_from_bytes(data, false).unwrap();
+    assert_eq!(summary.version(), replay.version());
+    assert_eq!(summary.matchhistory_id(), Some(5569487));
+    assert_eq!(summary.map().filename(), replay.map().filename());
+    assert_eq!(
+        summary
+            .players()
+            .iter()
+            .map(|player| { player.name() })
+            .collect::<Vec<&str>>(),
+        vec!["madhax", "Quixalotl"]
+    );
+    assert_eq!(summary.length(), None);
+
+    let summary = Replay::summary_from_bytes(data, true).unwrap();
+    assert_eq!(summary.length(), Some(replay.length()));
+}
+
 #[test]
 fn parse_failure() {
     let data = [1, 2, 3];
@@ -38,6 +105,33 @@ fn parse_failure() {
     assert!(replay.is_err())
 }
 
+#[test]
+fn parse_truncated() {
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let err = Replay::from_bytes(&data[..data.len() - 10]).unwrap_err();
+    assert!(matches!(err, VaultError::Truncated { .. }));
+    assert_eq!(err.path(), "TICKS");
+}
+
+#[test]
+fn parse_invalid_chunk_signature() {
+    let mut data = include_bytes!("../replays/USvDAK_v10612.rec").to_vec();
+    let offset = data
+        .windows(8)
+        .position(|window| window == b"DATASDSC")
+        .unwrap();
+    data[offset..offset + 4].copy_from_slice(b"JUNK");
+    let err = Replay::from_bytes(&data).unwrap_err();
+    assert_eq!(
+        err,
+        VaultError::InvalidChunkSignature {
+            offset,
+            path: String::new(),
+            signature: "JUNK".to_string()
+        }
+    );
+}
+
 #[test]
 fn parse_success_ai() {
     let data = include_bytes!("../replays/vs_ai.rec");
@@ -87,6 +181,66 @@ fn parse_battlegroup() {
     );
 }
 
+#[test]
+fn parse_loadout() {
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let player = &replay.players()[0];
+    let loadout = player.loadout();
+    assert_eq!(loadout.len(), 28);
+    assert_eq!(
+        loadout
+            .iter()
+            .filter(|item| item.category() == LoadoutCategory::Battlegroup)
+            .count(),
+        3
+    );
+    let item = &loadout[0];
+    assert_eq!(item.id(), 453294);
+    assert_eq!(item.instance_id(), Some(1800799));
+    assert_eq!(item.profile_id(), player.profile_id());
+    assert_eq!(item.metadata(), Some("{\"epos\":\"0\",\"eslot\":\"0\"}"));
+    assert_eq!(item.timestamp(), Some(1677175672));
+
+    let cosmetic = &loadout[3];
+    assert_eq!(cosmetic.category(), LoadoutCategory::Cosmetic);
+    let catalog = LoadoutCatalog::new(vec![(cosmetic.id(), LoadoutCategory::UnitSkin)]);
+    assert_eq!(cosmetic.category_in(&catalog), LoadoutCategory::UnitSkin);
+    assert_eq!(item.category_in(&catalog), LoadoutCategory::Battlegroup);
+}
+
+#[test]
+fn parse_malformed_loadout_item() {
+    let mut data = include_bytes!("../replays/USvDAK_v10612.rec").to_vec();
+    // give the first item's metadata a length that runs past the end of the item. the instance ID
+    // also appears in the header before the item data, so look for its last occurrence
+    let instance_id = 1800799u64.to_le_bytes();
+    let position = data
+        .windows(instance_id.len())
+        .rposition(|window| window == instance_id)
+        .unwrap();
+    data[position + 41..position + 45].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    let replay = Replay::from_bytes(&data).unwrap();
+    let loadout = replay.players()[0].loadout();
+    assert_eq!(loadout.len(), 27);
+    assert_ne!(loadout[0].instance_id(), Some(1800799));
+    assert!(loadout.iter().all(|item| item.instance_id().is_some()));
+}
+
+#[test]
+fn parse_cpu_loadout() {
+    let data = include_bytes!("../replays/vs_ai.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let loadout = replay.players()[1].loadout();
+    assert_eq!(loadout.len(), 3);
+    assert!(loadout
+        .iter()
+        .all(|item| item.category() == LoadoutCategory::Battlegroup));
+    assert_eq!(loadout[0].id(), 2033184);
+    assert_eq!(loadout[0].instance_id(), None);
+}
+
 #[test]
 fn parse_automatch() {
     let data = include_bytes!("../replays/automatch.rec");
@@ -95,6 +249,62 @@ fn parse_automatch() {
     assert_eq!(replay.matchhistory_id(), Some(18837622));
 }
 
+#[test]
+fn parse_info_chunks() {
+    let data = include_bytes!("../replays/automatch.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    assert_eq!(replay.player_ids(), vec![1, 0]);
+    assert_eq!(replay.matchtype_id(), Some(20));
+    assert_eq!(replay.recorder_id(), Some(0));
+
+    let data = include_bytes!("../replays/one_delimited_options.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let mut ids = replay
+        .players()
+        .iter()
+        .map(|player| player.id())
+        .collect::<Vec<u32>>();
+    ids.sort();
+    assert_eq!(replay.player_ids(), ids);
+
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    assert_eq!(replay.player_ids(), vec![0, 1]);
+    assert_eq!(replay.matchtype_id(), None);
+    assert_eq!(replay.recorder_id(), None);
+}
+
+#[test]
+fn parse_unexpected_info_chunk() {
+    let mut data = include_bytes!("../replays/automatch.rec").to_vec();
+    let header = data
+        .windows(8)
+        .position(|window| window == b"DATAPLAS")
+        .unwrap();
+    let name_length = u32::from_le_bytes(data[header + 16..header + 20].try_into().unwrap());
+    let body = header + 20 + name_length as usize;
+    // claim more player IDs than the chunk holds
+    data[body..body + 4].copy_from_slice(&u32::MAX.to_le_bytes());
+
+    let replay = Replay::from_bytes(&data).unwrap();
+    assert!(replay.player_ids().is_empty());
+    assert_eq!(replay.matchtype_id(), Some(20));
+}
+
+#[test]
+fn parse_recorded_by() {
+    let data = include_bytes!("../replays/ai_takeover.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    assert_eq!(
+        replay.recorded_by().map(|player| player.name()),
+        Some("Terza")
+    );
+
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    assert!(replay.recorded_by().is_none());
+}
+
 #[test]
 fn parse_custom() {
     let data = include_bytes!("../replays/custom.rec");
@@ -123,6 +333,26 @@ fn parse_new_map_chunk() {
     assert_eq!(replay.map_localized_description_id(), "$11233955");
 }
 
+#[test]
+fn parse_settings() {
+    let data = include_bytes!("../replays/USvDAK_v10612.rec");
+    let settings = Replay::from_bytes(data).unwrap().settings();
+    assert_eq!(settings.resources(), Some(0));
+    assert_eq!(settings.victory_points(), Some(500));
+    assert_eq!(settings.population_cap(), None);
+    assert_eq!(settings.win_condition(), Some(WinCondition::VictoryPoints));
+    assert_eq!(settings.options().len(), 2);
+
+    let data = include_bytes!("../replays/unusual_cpu_items.rec");
+    let settings = Replay::from_bytes(data).unwrap().settings();
+    assert_eq!(settings.population_cap(), Some(200));
+    assert_eq!(settings.starting_resources(), Some(2));
+    assert_eq!(settings.construction_speed(), Some(3));
+    assert_eq!(settings.get("ceasefire"), Some(0));
+    assert_eq!(settings.win_condition(), Some(WinCondition::VictoryPoints));
+    assert_eq!(MatchSettings::default().win_condition(), None);
+}
+
 #[test]
 fn parse_ai_takeover() {
     let data = include_bytes!("../replays/ai_takeover.rec");
@@ -130,6 +360,1015 @@ fn parse_ai_takeover() {
     assert!(replay.is_ok());
 }
 
+#[test]
+fn parse_ai_takeover_tick() {
+    let data = include_bytes!("../replays/ai_takeover.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let taken_over = replay
+        .players()
+        .into_iter()
+        .filter_map(|player| {
+            player
+                .ai_takeover_tick()
+                .map(|tick| (player.name().to_string(), tick.ticks()))
+        })
+        .collect::<Vec<(String, u32)>>();
+    assert_eq!(taken_over, vec![("WeliWest".to_string(), 15745)]);
+    assert!(replay
+        .players()
+        .iter()
+        .all(|player| player.ai_profile().is_none()));
+}
+
+#[test]
+fn parse_ai_profile() {
+    let data = include_bytes!("../replays/unusual_team_id.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let mut profiles = replay
+        .players()
+        .iter()
+        .filter_map(|player| player.ai_profile())
+        .collect::<Vec<AiProfile>>();
+    profiles.sort_by_key(|profile| profile.to_string());
+    assert_eq!(
+        profiles,
+        vec![
+            AiProfile::Expert,
+            AiProfile::Standard,
+            AiProfile::Standard,
+            AiProfile::Standard
+        ]
+    );
+
+    let data = include_bytes!("../replays/one_delimited_options.rec");
+    let replay = Replay::from_bytes(data).unwrap();
+    let cpu = replay
+        .players()
+        .into_iter()
+        .find(|player| player.ai_personality() == Some("campaign_ai_personality"))
+        .unwrap();
+    assert_eq!(cpu.ai_profile(), Some(AiProfile::Expert));
+    assert_eq!(cpu.ai_takeover_tick(), None);
+}
+
+#[test]
+fn parse_move_commands() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let players = replay.players();
+    let commands = players[1].commands();
+    assert!(!commands.iter().any(|command| {
+        matches!(command, Command::Unknown(data) if data.action_type() == CommandType::SCMD_Move)
+    }));
+    let first = commands
+        .into_iter()
+        .find_map(|command| match command {
+            Command::Move(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(first.tick().ticks(), 66);
+    assert_eq!(first.index(), 1);
+    assert_eq!(first.squad_ids(), &[50001]);
+    assert!(first.entity_ids().is_empty());
+    let position = first.position().unwrap();
+    assert_eq!(position.x(), 56.886086);
+    assert_eq!(position.y(), 65.1771);
+    assert_eq!(position.z(), -122.27823);
+
+    let retreat = players[0]
+        .commands()
+        .into_iter()
+        .find_map(|command| match command {
+            Command::Retreat(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(retreat.tick().ticks(), 1278);
+    assert_eq!(retreat.squad_ids(), &[50012]);
+    assert_eq!(retreat.target(), None);
+    assert!(!retreat.queued());
+}
+
+#[test]
+fn parse_attack_move_commands() {
+    let replay = Replay::from_path("replays/ai_takeover.rec").unwrap();
+    let attack_moves: Vec<_> = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .filter_map(|command| match command {
+            Command::AttackMove(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    assert!(!attack_moves.is_empty());
+    assert!(attack_moves
+        .iter()
+        .all(|command| command.position().is_some()));
+    assert!(attack_moves.iter().any(|command| command.queued()));
+}
+
+#[test]
+fn parse_construct_entity_commands() {
+    let replay = Replay::from_path("replays/ai_takeover.rec").unwrap();
+    let placement = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .find_map(|command| match command {
+            Command::ConstructEntity(data) if data.tick().ticks() == 3036 => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(placement.index(), 209);
+    assert_eq!(placement.pbgid(), 167199);
+    assert_eq!(placement.position().x(), 17.5);
+    assert_eq!(placement.position().z(), -87.5);
+    assert!(!placement.is_line());
+    assert_eq!(placement.rotation().round(), 38.0);
+    assert_eq!(placement.squad_ids(), &[50036]);
+
+    let replay = Replay::from_path("replays/one_delimited_options.rec").unwrap();
+    let placement = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .find_map(|command| match command {
+            Command::ConstructEntity(data) if data.tick().ticks() == 2626 => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(placement.pbgid(), 169973);
+    assert!(placement.is_line());
+    assert_eq!(placement.end_position().x().round(), 44.0);
+    assert_eq!(placement.squad_ids(), &[50012]);
+}
+
+#[test]
+fn parse_outcome() {
+    let replay = Replay::from_path("replays/one_seven_zero.rec").unwrap();
+    let outcome = replay.outcome();
+    assert_eq!(outcome.winner(), Some(Team::Second));
+    assert_eq!(outcome.reason(), EndReason::Surrender);
+    assert_eq!(outcome.confidence(), Confidence::High);
+    assert_eq!(outcome.result_for(0), Some(MatchResult::Loss));
+    assert_eq!(outcome.result_for(5), Some(MatchResult::Win));
+    assert_eq!(outcome.results().len(), 6);
+    let players = replay.players();
+    let squisl = players.iter().find(|player| player.id() == 0).unwrap();
+    assert_eq!(squisl.surrender_tick(), Some(GameTime::from_ticks(21127)));
+
+    let replay = Replay::from_path("replays/automatch.rec").unwrap();
+    let outcome = replay.outcome();
+    assert_eq!(outcome.winner(), Some(Team::First));
+    assert_eq!(outcome.reason(), EndReason::Disconnect);
+    assert_eq!(outcome.confidence(), Confidence::Medium);
+
+    let replay = Replay::from_path("replays/custom.rec").unwrap();
+    let outcome = replay.outcome();
+    assert_eq!(outcome.winner(), None);
+    assert_eq!(outcome.reason(), EndReason::Unknown);
+    assert_eq!(outcome.confidence(), Confidence::Low);
+    assert_eq!(outcome.result_for(0), Some(MatchResult::Unknown));
+    assert_eq!(outcome.result_for(9), None);
+}
+
+#[test]
+fn parse_integrity_report() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let report = replay.integrity_report(&[]);
+    assert!(report.cheats().is_empty());
+    assert_eq!(report.unapproved_mod(), None);
+    assert!(report.anomalies().is_empty());
+    assert!(!replay.is_tainted());
+
+    let replay = Replay::from_path("replays/vs_ai.rec").unwrap();
+    let mod_uuid = uuid!("385d9810-96ba-4ece-9040-8281db65174e");
+    assert!(replay.is_tainted());
+    assert_eq!(
+        replay.integrity_report(&[]).unapproved_mod(),
+        Some(mod_uuid)
+    );
+    assert!(!replay.integrity_report(&[mod_uuid]).is_tainted());
+}
+
+#[test]
+fn parse_cheats() {
+    let mut data = include_bytes!("../replays/USvDAK_v10612.rec").to_vec();
+    // turn an AI takeover into a resource cheat, which has a compatible layout
+    retype_command(
+        &mut data,
+        "000200000001000000035804da00010000e80300008030000000000000050404000000",
+        CommandType::PCMD_CheatResources,
+    );
+    // and an attack order into a reveal all cheat, which doesn't
+    retype_command(
+        &mut data,
+        "0042010000040000000207020000010000e803000000000000420d000020190000200105046bc30000",
+        CommandType::PCMD_CheatRevealAll,
+    );
+    let replay = Replay::from_bytes(&data).unwrap();
+    assert!(replay.is_tainted());
+
+    let cheats = replay.integrity_report(&[]).cheats();
+    assert_eq!(cheats.len(), 2);
+    assert_eq!(cheats[0].player_id(), 0);
+    assert_eq!(cheats[0].tick().ticks(), 4144);
+    assert_eq!(cheats[0].kind(), CheatKind::RevealAll);
+    assert!(cheats[0].cheat().is_none());
+    assert_eq!(cheats[1].tick().ticks(), 12419);
+    assert_eq!(cheats[1].kind(), CheatKind::Resources);
+    let cheat = cheats[1].cheat().unwrap();
+    assert_eq!(cheat.index(), 2);
+    assert_eq!(cheat.parameters(), &[4, 0, 0, 0]);
+}
+
+// replaces the type of the command whose data (starting at the player ID) is the given hex string
+fn retype_command(data: &mut [u8], command: &str, command_type: CommandType) {
+    let position = command_position(data, command);
+    data[position - 1] = command_type.into();
+}
+
+// finds the command whose data (starting at the player ID) is the given hex string
+fn command_position(data: &[u8], command: &str) -> usize {
+    let bytes: Vec<u8> = (0..command.len())
+        .step_by(2)
+        .map(|idx| u8::from_str_radix(&command[idx..idx + 2], 16).unwrap())
+        .collect();
+    data.windows(bytes.len())
+        .position(|window| window == bytes)
+        .unwrap()
+}
+
+#[test]
+fn parse_squad_ability_commands() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let commands: Vec<_> = replay
+        .players()
+        .iter()
+        .filter(|player| player.id() == 0)
+        .flat_map(|player| player.commands())
+        .collect();
+    assert!(!commands.iter().any(|command| {
+        matches!(command, Command::Unknown(data) if matches!(
+            data.action_type(),
+            CommandType::SCMD_Ability | CommandType::SCMD_Upgrade | CommandType::SCMD_ReinforceUnit
+        ))
+    }));
+    let abilities: Vec<_> = commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::UseSquadAbility(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+
+    let grenade = abilities
+        .iter()
+        .find(|data| data.tick().ticks() == 8505)
+        .unwrap();
+    assert_eq!(grenade.index(), 821);
+    assert_eq!(grenade.pbgid(), Some(226919));
+    assert_eq!(grenade.squad_ids(), &[50008]);
+    let position = grenade.position().unwrap();
+    assert_eq!(position.x(), 64.47954);
+    assert_eq!(position.y(), 68.23282);
+    assert_eq!(position.z(), 26.617683);
+
+    let interaction = abilities
+        .iter()
+        .find(|data| data.tick().ticks() == 5664)
+        .unwrap();
+    assert_eq!(interaction.pbgid(), None);
+    assert_eq!(interaction.target(), Some(Target::Entity(1000141875)));
+
+    let upgrade = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::BuildSquadUpgrade(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(upgrade.tick().ticks(), 1560);
+    assert_eq!(upgrade.pbgid(), Some(226928));
+    assert_eq!(upgrade.squad_ids(), &[50000]);
+    assert_eq!(upgrade.target(), None);
+
+    let replay = Replay::from_path("replays/one_seven_zero.rec").unwrap();
+    let reinforcements: Vec<_> = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .filter_map(|command| match command {
+            Command::Reinforce(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(reinforcements.len(), 5);
+    assert!(reinforcements
+        .iter()
+        .all(|data| data.pbgid().is_some() && data.squad_ids().len() == 1));
+}
+
+#[test]
+fn parse_squad_micro_commands() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let stop = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .find_map(|command| match command {
+            Command::Stop(data) => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(stop.tick().ticks(), 1857);
+    assert_eq!(stop.squad_ids(), &[50019]);
+    assert_eq!(stop.target(), None);
+
+    let replay = Replay::from_path("replays/one_char_options.rec").unwrap();
+    let faces: Vec<_> = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .filter_map(|command| match command {
+            Command::Face(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    let point = faces
+        .iter()
+        .find(|data| data.tick().ticks() == 7711)
+        .unwrap();
+    assert_eq!(point.squad_ids(), &[50088]);
+    let position = point.position().unwrap();
+    assert_eq!(position.x(), -63.668404);
+    assert_eq!(position.z(), 228.02348);
+    assert!(!point.queued());
+    let squad = faces
+        .iter()
+        .find(|data| data.tick().ticks() == 9066)
+        .unwrap();
+    assert_eq!(squad.target(), Some(Target::Squad(50092)));
+
+    let replay = Replay::from_path("replays/ai_takeover.rec").unwrap();
+    let direction = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .find_map(|command| match command {
+            Command::Face(data) if data.tick().ticks() == 1427 => data.target(),
+            _ => None,
+        })
+        .unwrap();
+    match direction {
+        Target::Direction(vector) => {
+            assert_eq!(vector.x(), -0.92484266);
+            assert_eq!(vector.z(), -0.3794855);
+        }
+        _ => panic!("expected a direction, got {:?}", direction),
+    }
+}
+
+#[test]
+fn parse_transport_commands() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let commands: Vec<_> = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .collect();
+    let loads: Vec<_> = commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::Load(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    let building = loads
+        .iter()
+        .find(|data| data.tick().ticks() == 2225)
+        .unwrap();
+    assert_eq!(building.squad_ids(), &[50020]);
+    assert_eq!(building.target(), Some(Target::Entity(1000137740)));
+    assert!(!building.queued());
+    let transport = loads
+        .iter()
+        .find(|data| data.tick().ticks() == 5660)
+        .unwrap();
+    assert_eq!(transport.squad_ids(), &[50008]);
+    assert_eq!(transport.target(), Some(Target::Squad(50032)));
+
+    let unloads: Vec<_> = commands
+        .iter()
+        .filter_map(|command| match command {
+            Command::UnloadSquads(data) => Some(data),
+            _ => None,
+        })
+        .collect();
+    let entity = unloads
+        .iter()
+        .find(|data| data.tick().ticks() == 3114)
+        .unwrap();
+    assert!(entity.squad_ids().is_empty());
+    assert_eq!(entity.entity_ids(), &[1000137837]);
+    let squad = unloads
+        .iter()
+        .find(|data| data.tick().ticks() == 6023)
+        .unwrap();
+    assert_eq!(squad.squad_ids(), &[50032]);
+    assert_eq!(squad.target(), None);
+
+    let replay = Replay::from_path("replays/ai_takeover.rec").unwrap();
+    let capture = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .find_map(|command| match command {
+            Command::CaptureTeamWeapon(data) if data.tick().ticks() == 10222 => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(capture.squad_ids(), &[50026]);
+    assert_eq!(capture.target(), Some(Target::Entity(1000013974)));
+}
+
+#[test]
+fn follow_squad_across_commands() {
+    let replay = Replay::from_path("replays/one_char_options.rec").unwrap();
+    let commands = replay.players()[0].commands();
+    let capture = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::Capture(data) if data.tick().ticks() == 93 => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    let placement = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::ConstructEntity(data) if data.tick().ticks() == 989 => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(capture.squad_ids(), placement.squad_ids());
+
+    // a squad loads into a halftrack, which is then ordered to unload it
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let commands: Vec<_> = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .collect();
+    let transport = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::Load(data) if data.tick().ticks() == 5660 => data.target(),
+            _ => None,
+        })
+        .unwrap();
+    let unload = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::UnloadSquads(data) if data.tick().ticks() == 6023 => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(transport, Target::Squad(unload.squad_ids()[0]));
+
+    // a team weapon crewed by one player's squads is later taken over by another player
+    let replay = Replay::from_path("replays/ai_takeover.rec").unwrap();
+    let mut crews = Vec::new();
+    for player in replay.players() {
+        for command in player.commands() {
+            if let Command::CaptureTeamWeapon(data) = command {
+                if data.target() == Some(Target::Entity(1000017669)) {
+                    crews.push((player.id(), data.tick().ticks(), data.squad_ids().to_vec()));
+                }
+            }
+        }
+    }
+    assert_eq!(
+        crews,
+        vec![
+            (3, 10922, vec![50126, 50042, 50055]),
+            (0, 11431, vec![50040])
+        ]
+    );
+}
+
+#[test]
+fn parse_capture_timeline() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let timeline = replay.capture_timeline();
+    assert_eq!(timeline.len(), 15);
+    let first = &timeline[0];
+    assert_eq!(first.entity_id(), 1000141279);
+    let captures = first.captures();
+    assert_eq!(captures[0].tick().ticks(), 54);
+    assert_eq!(captures[0].player_id(), 0);
+    assert_eq!(captures[0].squad_ids(), &[50000]);
+
+    let contested = timeline
+        .iter()
+        .find(|point| point.entity_id() == 1000141277)
+        .unwrap();
+    assert_eq!(
+        contested.first_capture_by(1),
+        Some(GameTime::from_ticks(74))
+    );
+    assert_eq!(
+        contested.first_capture_by(0),
+        Some(GameTime::from_ticks(1970))
+    );
+    assert!(contested
+        .captures()
+        .windows(2)
+        .all(|pair| pair[0].tick() <= pair[1].tick()));
+}
+
+#[test]
+fn parse_events() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let events: Vec<Event> = replay.events().collect();
+    assert_eq!(events.len(), 9161);
+    assert!(events
+        .windows(2)
+        .all(|pair| pair[0].tick() <= pair[1].tick()));
+    // player 1's first command was executed before player 0's on the same tick
+    assert_eq!(events[1].tick(), events[2].tick());
+    assert_eq!((events[1].player_id(), events[2].player_id()), (1, 0));
+
+    for player in replay.players() {
+        let commands: Vec<_> = events
+            .iter()
+            .filter(|event| event.player_id() == player.id())
+            .filter_map(|event| event.command().cloned())
+            .collect();
+        assert_eq!(commands, player.commands());
+    }
+    let system = events
+        .iter()
+        .filter(|event| matches!(event.kind(), EventKind::SystemCommand(_)))
+        .count();
+    assert_eq!(system, 6942);
+
+    let messages: Vec<_> = events.iter().filter_map(|event| event.message()).collect();
+    assert_eq!(messages.len(), 8);
+    assert_eq!(messages[0].message(), "glhf");
+    assert_eq!(messages[0].tick().ticks(), 154);
+    assert!(messages
+        .iter()
+        .all(|message| message.tick() <= replay.duration()));
+
+    let production: Vec<_> = replay
+        .events()
+        .between(GameTime::from_secs(60), GameTime::from_secs(120))
+        .player(1)
+        .category(CommandCategory::Production)
+        .collect();
+    assert_eq!(production.len(), 1);
+    assert_eq!(production[0].tick().ticks(), 917);
+    assert!(replay
+        .events()
+        .since(GameTime::from_ticks(12400))
+        .until(GameTime::from_ticks(12420))
+        .any(|event| event.message().is_some()));
+}
+
+#[test]
+fn resolve_attributes() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let db = Attributes::new(
+        10612,
+        vec![
+            Attribute::new(
+                198340,
+                "afrika_korps_squad".to_string(),
+                AttributeCategory::Squad,
+                Some(Faction::AfrikaKorps),
+                Cost::new(280.0, 0.0, 0.0, 5.0),
+            ),
+            Attribute::new(
+                196934,
+                "americans_battlegroup".to_string(),
+                AttributeCategory::Battlegroup,
+                Some(Faction::Americans),
+                Cost::default(),
+            ),
+        ],
+    );
+
+    let players = replay.players();
+    let build = &players[0].build_commands()[0];
+    assert_eq!(build.pbgid(), Some(198340));
+    let squad = build.resolve(&db).unwrap();
+    assert_eq!(squad.name(), "afrika_korps_squad");
+    assert_eq!(squad.category(), AttributeCategory::Squad);
+    assert_eq!(squad.faction(), Some(players[0].faction()));
+    assert_eq!(squad.cost().manpower(), 280.0);
+
+    let battlegroup = &players[1].battlegroup_commands()[0];
+    assert_eq!(
+        battlegroup
+            .resolve(&db)
+            .map(|attribute| attribute.category()),
+        Some(AttributeCategory::Battlegroup)
+    );
+    // pbgids missing from the database and commands without one don't resolve
+    assert!(players[1].build_commands()[0].resolve(&db).is_none());
+    let movement = players[0]
+        .commands()
+        .into_iter()
+        .find(|command| matches!(command, Command::Move(_)))
+        .unwrap();
+    assert_eq!(movement.pbgid(), None);
+    assert!(movement.resolve(&db).is_none());
+}
+
+#[test]
+#[cfg(feature = "attributes")]
+fn load_attributes() {
+    let json = br#"{
+        "10612": [
+            {
+                "pbgid": 198340,
+                "name": "afrika_korps_squad",
+                "category": "Squad",
+                "faction": "AfrikaKorps",
+                "cost": { "manpower": 280.0, "munitions": 0.0, "fuel": 0.0, "popcap": 5.0 },
+                "localized_name_id": "$11233954"
+            },
+            { "pbgid": 1, "name": "neutral_entity", "category": "Entity" }
+        ],
+        "9000": [{ "pbgid": 198340, "name": "old_squad", "category": "Squad" }]
+    }"#;
+    let json_archive = AttributeArchive::from_json_slice(json).unwrap();
+    assert_eq!(json_archive.versions(), vec![9000, 10612]);
+    let json_db = json_archive.for_version(10612).unwrap();
+    assert_eq!(json_db.len(), 2);
+    assert_eq!(json_db.attribute(198340).unwrap().cost().popcap(), 5.0);
+    assert_eq!(json_db.attribute(1).unwrap().faction(), None);
+    // builds without their own attributes fall back to the newest earlier build
+    assert_eq!(json_archive.for_version(10000).unwrap().version(), 9000);
+    assert_eq!(json_archive.for_version(11000).unwrap().version(), 10612);
+    assert!(json_archive.for_version(8000).is_none());
+
+    let csv =
+        "version,pbgid,name,category,faction,manpower,munitions,fuel,popcap,localized_name_id\n\
+               10612,198340,afrika_korps_squad,Squad,AfrikaKorps,280,0,0,5,$11233954\n\
+               10612,1,neutral_entity,Entity,,0,0,0,0,\n";
+    let archive = AttributeArchive::from_csv_reader(csv.as_bytes()).unwrap();
+    assert_eq!(archive.versions(), vec![10612]);
+    let db = archive.for_version(10612).unwrap();
+    assert_eq!(db.attribute(198340), json_db.attribute(198340));
+    assert_eq!(
+        db.attribute(1).unwrap().category(),
+        AttributeCategory::Entity
+    );
+
+    let error =
+        AttributeArchive::from_csv_reader("version,pbgid\n10612,abc\n".as_bytes()).unwrap_err();
+    assert!(matches!(
+        error,
+        AttributeError::Malformed { line: Some(2), .. }
+    ));
+    assert!(matches!(
+        AttributeArchive::from_json_path("attributes/missing.json"),
+        Err(AttributeError::Io { .. })
+    ));
+}
+
+fn ucs(text: &str) -> Vec<u8> {
+    std::iter::once(0xfeff)
+        .chain(text.encode_utf16())
+        .flat_map(|unit: u16| unit.to_le_bytes())
+        .collect()
+}
+
+#[test]
+fn parse_locale() {
+    let english = Locale::from_ucs_reader(
+        "english",
+        &ucs("11233954\tDesert Airfield\r\n11233955\tAn airfield.\tWith a tab.\r\n\r\n42\r\n")[..],
+    )
+    .unwrap();
+    assert_eq!(english.language(), "english");
+    assert_eq!(english.len(), 3);
+    assert_eq!(english.get(11233955), Some("An airfield.\tWith a tab."));
+    assert_eq!(english.get(42), Some(""));
+    assert_eq!(english.resolve("$11233954"), Some("Desert Airfield"));
+    assert_eq!(english.resolve("11233954"), None);
+    let german = Locale::from_ucs_bytes("german", &ucs("11233954\tWüstenflugplatz\n")).unwrap();
+
+    let replay = Replay::from_path("replays/one_seven_zero.rec").unwrap();
+    let map = replay.map();
+    assert_eq!(map.localized_name(&english), Some("Desert Airfield"));
+    assert_eq!(
+        map.localized_description(&english),
+        Some("An airfield.\tWith a tab.")
+    );
+    assert_eq!(map.localized_name(&german), Some("Wüstenflugplatz"));
+    assert_eq!(map.localized_description(&german), None);
+    // maps that store their name directly don't need a locale
+    let replay = Replay::from_path("replays/weird_description.rec").unwrap();
+    assert_eq!(
+        replay.map().localized_name(&german),
+        Some("Twin Beaches ML")
+    );
+
+    let attribute = Attribute::new(
+        198340,
+        "afrika_korps_squad".to_string(),
+        AttributeCategory::Squad,
+        None,
+        Cost::default(),
+    )
+    .with_localized_name_id("$11233954".to_string());
+    assert_eq!(attribute.localized_name(&german), Some("Wüstenflugplatz"));
+
+    let locales = Locales::new(vec![german, english]);
+    assert_eq!(locales.languages(), vec!["english", "german"]);
+    assert_eq!(
+        locales.resolve("english", "$11233954"),
+        Some("Desert Airfield")
+    );
+    assert_eq!(
+        locales.resolve("german", "$11233954"),
+        Some("Wüstenflugplatz")
+    );
+    assert_eq!(locales.resolve("french", "$11233954"), None);
+
+    // a stray line doesn't lose the rest of the language
+    let partial = Locale::from_ucs_bytes("english", &ucs("1\tone\nx\ttwo\n3\tthree\n")).unwrap();
+    assert_eq!(partial.len(), 2);
+    assert_eq!(partial.get(3), Some("three"));
+    assert_eq!(partial.skipped_lines(), &[2]);
+    assert!(matches!(
+        Locale::from_ucs_bytes("english", &[0xff, 0xfe, 0x31]),
+        Err(LocaleError::Malformed { .. })
+    ));
+}
+
+#[test]
+fn parse_cancel_commands() {
+    let replay = Replay::from_path("replays/one_seven_zero.rec").unwrap();
+    let commands: Vec<_> = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .collect();
+    let cancel = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::CancelProduction(data) if data.tick().ticks() == 4992 => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(cancel.index(), 300);
+    assert_eq!(cancel.queue_index(), 4);
+
+    // cancelling an upgrade the squad was given a few seconds earlier
+    let upgrade = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::BuildSquadUpgrade(data) if data.tick().ticks() == 12384 => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    let cancel = commands
+        .iter()
+        .find_map(|command| match command {
+            Command::CancelSquadProduction(data) if data.tick().ticks() == 12397 => Some(data),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(cancel.index(), 1690);
+    assert_eq!(cancel.squad_ids(), &[50165]);
+    assert_eq!(cancel.squad_ids(), upgrade.squad_ids());
+    assert!(cancel.entity_ids().is_empty());
+    assert_eq!(cancel.queue_index(), 1);
+
+    let replay = Replay::from_path("replays/ai_takeover.rec").unwrap();
+    let commands: Vec<_> = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .collect();
+    assert!(!commands.iter().any(|command| {
+        matches!(command, Command::Unknown(data) if data.action_type() == CommandType::PCMD_TentativeUpgradeRemoveAll)
+    }));
+    let deselect = commands
+        .iter()
+        .filter(|command| matches!(command, Command::DeselectBattlegroupAbilities(_)))
+        .min_by_key(|command| command.tick())
+        .unwrap();
+    assert_eq!(deselect.tick().ticks(), 80);
+}
+
+#[test]
+fn parse_production() {
+    let replay = Replay::from_path("replays/ai_takeover.rec").unwrap();
+    let player = replay
+        .players()
+        .into_iter()
+        .find(|player| player.id() == 0)
+        .unwrap();
+    let production = player.production();
+    let cancelled: Vec<_> = production.iter().filter(|item| item.cancelled()).collect();
+    assert_eq!(cancelled.len(), 3);
+    assert_eq!(cancelled[0].tick().ticks(), 1845);
+    assert_eq!(cancelled[0].queue_index(), 1);
+    assert_eq!(cancelled[0].cancel_tick(), Some(GameTime::from_ticks(1854)));
+    assert_eq!(cancelled[1].tick().ticks(), 9003);
+    assert_eq!(cancelled[1].cancel_tick(), Some(GameTime::from_ticks(9025)));
+    assert_eq!(cancelled[2].tick().ticks(), 9007);
+    assert_eq!(cancelled[2].queue_index(), 3);
+    assert_eq!(cancelled[2].cancel_tick(), Some(GameTime::from_ticks(9022)));
+    assert!(matches!(
+        cancelled[2].command(),
+        Command::BuildGlobalUpgrade(_)
+    ));
+
+    let build_order = player.effective_build_order();
+    assert_eq!(build_order.len(), player.build_commands().len() - 3);
+    assert!(!build_order
+        .iter()
+        .any(|command| [1845, 9003, 9007].contains(&command.tick().ticks())));
+    assert_eq!(cancelled[2].build().index(), cancelled[2].command().index());
+
+    // squad upgrades are cancelled through the squad rather than a structure's queue
+    let replay = Replay::from_path("replays/one_seven_zero.rec").unwrap();
+    let player = replay
+        .players()
+        .into_iter()
+        .find(|player| player.id() == 1)
+        .unwrap();
+    assert!(player.unmatched_cancels().is_empty());
+    let build_order = player.effective_build_order();
+    assert!(player
+        .build_commands()
+        .iter()
+        .any(|command| command.tick().ticks() == 12384));
+    assert!(!build_order
+        .iter()
+        .any(|command| command.tick().ticks() == 12384));
+
+    // a player level cancel isn't decoded, so it's surfaced instead of removing anything
+    let mut data = fs::read("replays/one_seven_zero.rec").unwrap();
+    retype_command(
+        &mut data,
+        "019a0600000000000002b7f8bd01010000e903000000000000200000a5050401000000",
+        CommandType::PCMD_CancelProduction,
+    );
+    let replay = Replay::from_bytes(&data).unwrap();
+    let player = replay
+        .players()
+        .into_iter()
+        .find(|player| player.id() == 1)
+        .unwrap();
+    let unmatched = player.unmatched_cancels();
+    assert_eq!(unmatched.len(), 1);
+    assert_eq!(unmatched[0].tick().ticks(), 12397);
+    assert!(player
+        .effective_build_order()
+        .iter()
+        .any(|command| command.tick().ticks() == 12384));
+
+    // command indexes aren't unique, so a build sharing its index with a cancelled one is kept
+    let mut data = fs::read("replays/USvDAK_v10612.rec").unwrap();
+    let position = command_position(
+        &data,
+        "001e020000000000000285744600010000e803000000000000100214c1150a01c4060300e803000000",
+    );
+    data[position + 1..position + 5].copy_from_slice(&540u32.to_le_bytes());
+    let replay = Replay::from_bytes(&data).unwrap();
+    let player = &replay.players()[0];
+    let build_order = player.effective_build_order();
+    assert_eq!(build_order.len(), player.build_commands().len() - 1);
+    assert!(!build_order
+        .iter()
+        .any(|command| command.tick().ticks() == 5787));
+    assert!(build_order
+        .iter()
+        .any(|command| command.tick().ticks() == 5851 && command.index() == 540));
+
+    for path in [
+        "replays/ai_takeover.rec",
+        "replays/unusual_options.rec",
+        "replays/weird_description.rec",
+        "replays/zero_items.rec",
+    ] {
+        let replay = Replay::from_path(path).unwrap();
+        for player in replay.players() {
+            assert!(player.unmatched_cancels().is_empty());
+        }
+    }
+}
+
+#[test]
+fn parse_apm() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let reports = apm::apm(&replay, &ApmOptions::default());
+    assert_eq!(reports.len(), 2);
+    let report = reports
+        .iter()
+        .find(|report| report.player_id() == 0)
+        .unwrap();
+    let player = replay
+        .players()
+        .into_iter()
+        .find(|player| player.id() == 0)
+        .unwrap();
+    let commands = player.commands();
+    let player_commands: Vec<_> = commands
+        .iter()
+        .filter(|command| command.index() != 0)
+        .collect();
+    assert_eq!(player_commands.len(), 1262);
+
+    // system commands aren't counted
+    assert_eq!(report.per_minute().len(), 26);
+    assert_eq!(
+        report.per_minute().iter().sum::<u32>() as usize,
+        player_commands.len()
+    );
+    assert_eq!(report.apm(), 1262.0 * 480.0 / 12456.0);
+
+    // ticks start at 1, so a command on the last tick of a minute belongs to that minute
+    assert!(player_commands
+        .iter()
+        .any(|command| command.tick().ticks() == 4800));
+    let tenth_minute = player_commands
+        .iter()
+        .filter(|command| (4321..=4800).contains(&command.tick().ticks()))
+        .count();
+    assert_eq!(report.per_minute()[9] as usize, tenth_minute);
+    // undecoded commands are never counted as repeats, since their targets aren't known
+    assert_eq!(report.effective_per_minute().iter().sum::<u32>(), 1215);
+    assert!(report.effective_apm() < report.apm());
+    assert!(report.effective_peak_apm() >= report.effective_apm());
+    assert_eq!(report.effective_apm(), 1215.0 * 480.0 / 12456.0);
+
+    // a window of 0 only catches repeats issued on the same tick
+    let strict = apm::player_apm(&player, replay.length(), &ApmOptions::new(0, 480));
+    assert_eq!(strict.effective_per_minute().iter().sum::<u32>(), 1261);
+
+    // a peak window that runs past the end of the match
+    let wide = apm::player_apm(&player, replay.length(), &ApmOptions::new(4, u32::MAX));
+    assert_eq!(
+        wide.peak_apm(),
+        player_commands.len() as f64 * 480.0 / u32::MAX as f64
+    );
+}
+
+#[test]
+fn parse_game_time() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let duration = replay.duration();
+    assert_eq!(duration.ticks(), 12456);
+    assert_eq!(duration.to_string(), "25:57");
+    assert_eq!(duration.as_duration(), Duration::from_secs(1557));
+
+    let first = replay
+        .players()
+        .iter()
+        .flat_map(|player| player.commands())
+        .map(|command| command.tick())
+        .min()
+        .unwrap();
+    assert!(first < duration);
+    assert_eq!(
+        first.as_duration(),
+        Duration::from_millis(first.ticks() as u64 * 125)
+    );
+    assert_eq!((duration - first) + first, duration);
+    assert_eq!(first.checked_sub(duration), None);
+    assert_eq!(GameTime::from_secs(61).to_string(), "01:01");
+    assert_eq!(GameTime::from_ticks(12).as_secs_f64(), 1.5);
+    assert_eq!(GameTime::from_secs(1).ticks(), TICKS_PER_SECOND);
+}
+
+#[test]
+fn parse_messages() {
+    let replay = Replay::from_path("replays/USvDAK_v10612.rec").unwrap();
+    let players = replay.players();
+    // message ticks count only the command ticks before them, so they line up with command ticks
+    let ticks: Vec<_> = players[0]
+        .messages()
+        .iter()
+        .map(|message| message.tick().ticks())
+        .collect();
+    assert_eq!(ticks, vec![154, 12080, 12215, 12246, 12264]);
+    let last = players[1].messages().pop().unwrap();
+    assert_eq!(last.tick().ticks(), 12413);
+    assert_eq!(
+        last.message(),
+        "DAK is a hard faction and needs buffs for sure"
+    );
+    assert!(last.tick() <= replay.duration());
+}
+
 #[test]
 fn parse_zero_item_player() {
     let data = include_bytes!("../replays/zero_items.rec");